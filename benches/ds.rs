@@ -1,189 +1,207 @@
-// // Benchmarks for data structures.
-
-// #[allow(unused_imports)]
-// use criterion::{black_box, criterion_group, criterion_main, Criterion};
-
-// use cykl::tour::TwoLevelList;
-// use cykl::tour::{Tour, TourImpltor};
-// use cykl::Scalar;
-// use cykl::{tour::Array, RepoBuilder};
-// use cykl::{NodeKind, Repo};
-// use tspf::WeightKind;
-
-// const INDEX: usize = 987_654;
-
-// #[allow(dead_code)]
-// pub fn create_repo(n_nodes: usize) -> Repo {
-//     let mut repo = RepoBuilder::new(WeightKind::Euc2d)
-//         .capacity(n_nodes)
-//         .build();
-//     for ii in 0..n_nodes {
-//         repo.add(NodeKind::Customer, vec![ii as Scalar; 3]);
-//     }
-//     repo
-// }
-
-// fn benchmark_get(c: &mut Criterion) {
-//     let repo = create_repo(1_000_000);
-
-//     let arr = Array::new(&repo);
-//     let arre: TourImpltor = Array::new(&repo).into();
-//     let tll = TwoLevelList::with_default_order(&repo, 100);
-//     let tlle: TourImpltor = TwoLevelList::with_default_order(&repo, 100).into();
-
-//     c.bench_function("Array Get", |b| b.iter(|| arr.get(black_box(INDEX - 1))));
-//     c.bench_function("TLL Get", |b| b.iter(|| tll.get(black_box(INDEX - 1))));
-//     c.bench_function("Array (ED) 1M Get", |b| {
-//         b.iter(|| arre.get(black_box(INDEX - 1)))
-//     });
-//     c.bench_function("TLL (ED) 1M Get", |b| {
-//         b.iter(|| tlle.get(black_box(INDEX - 1)))
-//     });
-// }
-
-// fn benchmark_successor(c: &mut Criterion) {
-//     let repo = create_repo(1_000_000);
-
-//     let arr = Array::new(&repo);
-//     let arre: TourImpltor = Array::new(&repo).into();
-//     let tll = TwoLevelList::with_default_order(&repo, 100);
-//     let tlle: TourImpltor = TwoLevelList::with_default_order(&repo, 100).into();
-
-//     c.bench_function("Array Successor", |b| {
-//         b.iter(|| arr.successor_at(black_box(INDEX - 1)))
-//     });
-//     c.bench_function("TLL Successor", |b| {
-//         b.iter(|| tll.successor_at(black_box(INDEX - 1)))
-//     });
-//     c.bench_function("Array (ED) Successor", |b| {
-//         b.iter(|| arre.successor_at(black_box(INDEX - 1)))
-//     });
-//     c.bench_function("TLL (ED) Successor", |b| {
-//         b.iter(|| tlle.successor_at(black_box(INDEX - 1)))
-//     });
-// }
-
-// fn benchmark_predecessor(c: &mut Criterion) {
-//     let repo = create_repo(1_000_000);
-
-//     let arr = Array::new(&repo);
-//     let arre: TourImpltor = Array::new(&repo).into();
-//     let tll = TwoLevelList::with_default_order(&repo, 100);
-//     let tlle: TourImpltor = TwoLevelList::with_default_order(&repo, 100).into();
-
-//     c.bench_function("Array Predecessor", |b| {
-//         b.iter(|| arr.get(black_box(INDEX - 1)))
-//     });
-//     c.bench_function("TLL Predecessor", |b| {
-//         b.iter(|| tll.get(black_box(INDEX - 1)))
-//     });
-//     c.bench_function("Array (ED) Predecessor", |b| {
-//         b.iter(|| arre.get(black_box(INDEX - 1)))
-//     });
-//     c.bench_function("TLL (ED) Predecessor", |b| {
-//         b.iter(|| tlle.get(black_box(INDEX - 1)))
-//     });
-// }
-
-// fn benchmark_between(c: &mut Criterion) {
-//     let repo = create_repo(1_000_000);
-
-//     let arr = Array::new(&repo);
-//     let arre: TourImpltor = Array::new(&repo).into();
-//     let tll = TwoLevelList::with_default_order(&repo, 100);
-//     let tlle: TourImpltor = TwoLevelList::with_default_order(&repo, 100).into();
-
-//     c.bench_function("Array Between", |b| {
-//         b.iter(|| arr.between_at(black_box(1), black_box(500_000), black_box(1_000_000 - 1)))
-//     });
-//     c.bench_function("TLL Between", |b| {
-//         b.iter(|| tll.between_at(black_box(1), black_box(500_000), black_box(1_000_000 - 1)))
-//     });
-//     c.bench_function("Array (ED) Between", |b| {
-//         b.iter(|| arre.between_at(black_box(1), black_box(500_000), black_box(1_000_000 - 1)))
-//     });
-//     c.bench_function("TLL (ED) Between", |b| {
-//         b.iter(|| tlle.between_at(black_box(1), black_box(500_000), black_box(1_000_000 - 1)))
-//     });
-// }
-
-// /// Flip an entire segment (99 elements).
-// fn benchmark_flip_case_1(c: &mut Criterion) {
-//     fn flip(tour: &mut impl Tour) {
-//         let left = black_box(0);
-//         let next_left = tour.successor_at(left).unwrap().index();
-//         let next_right = 100;
-//         let right = tour.predecessor_at(next_right).unwrap().index();
-//         tour.flip_at(left, next_left, right, next_right);
-//     }
-
-//     let repo = create_repo(1_000_000);
-
-//     let mut arr = Array::new(&repo);
-//     let mut arre: TourImpltor = Array::new(&repo).into();
-//     let mut tll = TwoLevelList::with_default_order(&repo, 100);
-//     let mut tlle: TourImpltor = TwoLevelList::with_default_order(&repo, 100).into();
-
-//     c.bench_function("Array Flip - Case 1", |b| b.iter(|| flip(&mut arr)));
-//     c.bench_function("TLL   Flip - Case 1", |b| b.iter(|| flip(&mut tll)));
-//     c.bench_function("Array (ED) Flip - Case 1", |b| b.iter(|| flip(&mut arre)));
-//     c.bench_function("TLL   (ED) Flip - Case 1", |b| b.iter(|| flip(&mut tlle)));
-// }
-
-// /// Flip an entire segment (100 elements).
-// fn benchmark_flip_case_2(c: &mut Criterion) {
-//     fn flip(tour: &mut impl Tour) {
-//         let left = black_box(99);
-//         let next_left = tour.successor_at(left).unwrap().index();
-//         let next_right = 200;
-//         let right = tour.predecessor_at(next_right).unwrap().index();
-//         tour.flip_at(left, next_left, right, next_right);
-//     }
-
-//     let repo = create_repo(1_000_000);
-
-//     let mut arr = Array::new(&repo);
-//     let mut arre: TourImpltor = Array::new(&repo).into();
-//     let mut tll = TwoLevelList::with_default_order(&repo, 100);
-//     let mut tlle: TourImpltor = TwoLevelList::with_default_order(&repo, 100).into();
-
-//     c.bench_function("Array Flip - Case 2", |b| b.iter(|| flip(&mut arr)));
-//     c.bench_function("TLL   Flip - Case 2", |b| b.iter(|| flip(&mut tll)));
-//     c.bench_function("Array (ED) Flip - Case 2", |b| b.iter(|| flip(&mut arre)));
-//     c.bench_function("TLL   (ED) Flip - Case 2", |b| b.iter(|| flip(&mut tlle)));
-// }
-
-// /// Flip multiple segments.
-// fn benchmark_flip_case_3(c: &mut Criterion) {
-//     fn flip(tour: &mut impl Tour) {
-//         let left = black_box(99);
-//         let next_left = tour.successor_at(left).unwrap().index();
-//         let next_right = 1000;
-//         let right = tour.predecessor_at(next_right).unwrap().index();
-//         tour.flip_at(left, next_left, right, next_right);
-//     }
-
-//     let repo = create_repo(1_000_000);
-
-//     let mut arr = Array::new(&repo);
-//     let mut arre: TourImpltor = Array::new(&repo).into();
-//     let mut tll = TwoLevelList::with_default_order(&repo, 100);
-//     let mut tlle: TourImpltor = TwoLevelList::with_default_order(&repo, 100).into();
-
-//     c.bench_function("Array Flip - Case 3", |b| b.iter(|| flip(&mut arr)));
-//     c.bench_function("TLL   Flip - Case 3", |b| b.iter(|| flip(&mut tll)));
-//     c.bench_function("Array (ED) Flip - Case 3", |b| b.iter(|| flip(&mut arre)));
-//     c.bench_function("TLL   (ED) Flip - Case 3", |b| b.iter(|| flip(&mut tlle)));
-// }
-
-// criterion_group!(
-//     benches,
-//     benchmark_get,
-//     benchmark_successor,
-//     benchmark_predecessor,
-//     benchmark_between,
-//     benchmark_flip_case_1,
-//     benchmark_flip_case_2,
-//     benchmark_flip_case_3,
-// );
-// criterion_main!(benches);
+// Benchmarks for data structures.
+
+#[allow(unused_imports)]
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use cykl::data::{DataStore, Metric, NodeKind};
+use cykl::tour::{Array, SplayTree, Tour, TwoLevelList};
+use cykl::Scalar;
+
+const N_NODES: usize = 2_000;
+const INDEX: usize = 1_234;
+
+#[allow(dead_code)]
+pub fn create_store(n_nodes: usize) -> DataStore<()> {
+    let mut store = create_large_store(n_nodes);
+    store.compute();
+    store
+}
+
+/// Creates a store without cost matrix, whose costs are computed on demand.
+#[allow(dead_code)]
+pub fn create_large_store(n_nodes: usize) -> DataStore<()> {
+    let mut store = DataStore::with_capacity(Metric::Euc3d, n_nodes);
+    for ii in 0..n_nodes {
+        store.add_coords(NodeKind::Target, &[ii as Scalar; 3], ());
+    }
+    store
+}
+
+fn benchmark_get(c: &mut Criterion) {
+    let store = create_store(N_NODES);
+
+    let arr = Array::new(&store);
+    let tll = TwoLevelList::new(&store, 100);
+    let spl = SplayTree::new(&store);
+
+    c.bench_function("Array Get", |b| b.iter(|| arr.get(black_box(INDEX - 1))));
+    c.bench_function("TLL Get", |b| b.iter(|| tll.get(black_box(INDEX - 1))));
+    c.bench_function("Splay Get", |b| b.iter(|| spl.get(black_box(INDEX - 1))));
+}
+
+fn benchmark_successor(c: &mut Criterion) {
+    let store = create_store(N_NODES);
+
+    let arr = Array::new(&store);
+    let tll = TwoLevelList::new(&store, 100);
+    let spl = SplayTree::new(&store);
+
+    c.bench_function("Array Successor", |b| {
+        b.iter(|| arr.successor_at(black_box(INDEX - 1)))
+    });
+    c.bench_function("TLL Successor", |b| {
+        b.iter(|| tll.successor_at(black_box(INDEX - 1)))
+    });
+    c.bench_function("Splay Successor", |b| {
+        b.iter(|| spl.successor_at(black_box(INDEX - 1)))
+    });
+}
+
+fn benchmark_predecessor(c: &mut Criterion) {
+    let store = create_store(N_NODES);
+
+    let arr = Array::new(&store);
+    let tll = TwoLevelList::new(&store, 100);
+    let spl = SplayTree::new(&store);
+
+    c.bench_function("Array Predecessor", |b| {
+        b.iter(|| arr.predecessor_at(black_box(INDEX - 1)))
+    });
+    c.bench_function("TLL Predecessor", |b| {
+        b.iter(|| tll.predecessor_at(black_box(INDEX - 1)))
+    });
+    c.bench_function("Splay Predecessor", |b| {
+        b.iter(|| spl.predecessor_at(black_box(INDEX - 1)))
+    });
+}
+
+fn benchmark_between(c: &mut Criterion) {
+    let store = create_store(N_NODES);
+
+    let arr = Array::new(&store);
+    let tll = TwoLevelList::new(&store, 100);
+    let spl = SplayTree::new(&store);
+
+    c.bench_function("Array Between", |b| {
+        b.iter(|| arr.between_at(black_box(1), black_box(N_NODES / 2), black_box(N_NODES - 1)))
+    });
+    c.bench_function("TLL Between", |b| {
+        b.iter(|| tll.between_at(black_box(1), black_box(N_NODES / 2), black_box(N_NODES - 1)))
+    });
+    c.bench_function("Splay Between", |b| {
+        b.iter(|| spl.between_at(black_box(1), black_box(N_NODES / 2), black_box(N_NODES - 1)))
+    });
+}
+
+/// Flip an entire segment (99 elements).
+fn benchmark_flip_case_1(c: &mut Criterion) {
+    fn flip(tour: &mut impl Tour) {
+        let left = black_box(0);
+        let next_left = tour.successor_at(left).unwrap().index();
+        let next_right = 100;
+        let right = tour.predecessor_at(next_right).unwrap().index();
+        tour.flip_at(left, next_left, right, next_right);
+    }
+
+    let store = create_store(N_NODES);
+
+    let mut arr = Array::new(&store);
+    let mut tll = TwoLevelList::new(&store, 100);
+    let mut spl = SplayTree::new(&store);
+
+    c.bench_function("Array Flip - Case 1", |b| b.iter(|| flip(&mut arr)));
+    c.bench_function("TLL   Flip - Case 1", |b| b.iter(|| flip(&mut tll)));
+    c.bench_function("Splay Flip - Case 1", |b| b.iter(|| flip(&mut spl)));
+}
+
+/// Flip an entire segment (100 elements).
+fn benchmark_flip_case_2(c: &mut Criterion) {
+    fn flip(tour: &mut impl Tour) {
+        let left = black_box(99);
+        let next_left = tour.successor_at(left).unwrap().index();
+        let next_right = 200;
+        let right = tour.predecessor_at(next_right).unwrap().index();
+        tour.flip_at(left, next_left, right, next_right);
+    }
+
+    let store = create_store(N_NODES);
+
+    let mut arr = Array::new(&store);
+    let mut tll = TwoLevelList::new(&store, 100);
+    let mut spl = SplayTree::new(&store);
+
+    c.bench_function("Array Flip - Case 2", |b| b.iter(|| flip(&mut arr)));
+    c.bench_function("TLL   Flip - Case 2", |b| b.iter(|| flip(&mut tll)));
+    c.bench_function("Splay Flip - Case 2", |b| b.iter(|| flip(&mut spl)));
+}
+
+/// Flip multiple segments.
+fn benchmark_flip_case_3(c: &mut Criterion) {
+    fn flip(tour: &mut impl Tour) {
+        let left = black_box(99);
+        let next_left = tour.successor_at(left).unwrap().index();
+        let next_right = 1000;
+        let right = tour.predecessor_at(next_right).unwrap().index();
+        tour.flip_at(left, next_left, right, next_right);
+    }
+
+    let store = create_store(N_NODES);
+
+    let mut arr = Array::new(&store);
+    let mut tll = TwoLevelList::new(&store, 100);
+    let mut spl = SplayTree::new(&store);
+
+    c.bench_function("Array Flip - Case 3", |b| b.iter(|| flip(&mut arr)));
+    c.bench_function("TLL   Flip - Case 3", |b| b.iter(|| flip(&mut tll)));
+    c.bench_function("Splay Flip - Case 3", |b| b.iter(|| flip(&mut spl)));
+}
+
+/// Flip random paths in a tour with one million nodes.
+fn benchmark_flip_large(c: &mut Criterion) {
+    fn flip(tour: &mut impl Tour, state: &mut usize) {
+        let n_nodes = tour.len();
+        // A simple linear congruential generator keeps the sequence of flips reproducible.
+        *state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1);
+        let left = (*state >> 33) % n_nodes;
+        *state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1);
+        let right = (*state >> 33) % n_nodes;
+
+        let next_left = tour.successor_at(left).unwrap().index();
+        let next_right = tour.successor_at(right).unwrap().index();
+        if left != right && next_left != right && left != next_right {
+            tour.flip_at(left, next_left, right, next_right);
+        }
+    }
+
+    let n_nodes = 1_000_000;
+    let store = create_large_store(n_nodes);
+
+    let mut tll = TwoLevelList::new(&store, 1_000);
+    let mut spl = SplayTree::new(&store);
+
+    let mut state = 0;
+    c.bench_function("TLL   1M Flip - Random", |b| {
+        b.iter(|| flip(&mut tll, &mut state))
+    });
+    let mut state = 0;
+    c.bench_function("Splay 1M Flip - Random", |b| {
+        b.iter(|| flip(&mut spl, &mut state))
+    });
+}
+
+criterion_group!(
+    benches,
+    benchmark_get,
+    benchmark_successor,
+    benchmark_predecessor,
+    benchmark_between,
+    benchmark_flip_case_1,
+    benchmark_flip_case_2,
+    benchmark_flip_case_3,
+    benchmark_flip_large,
+);
+criterion_main!(benches);
@@ -0,0 +1,372 @@
+use crate::{
+    tour::{Tour, TourNode},
+    Scalar,
+};
+
+use super::{CandidateScore, CandidateSet, NodeTable};
+
+/// Generates candidates for each node by using the alpha-nearness measure of LKH.
+///
+/// The alpha value of an edge is the increase in the cost of a minimum 1-tree which is required
+/// to contain that edge. Edges of the minimum 1-tree thus have an alpha value of zero. Each node
+/// receives the `k` other nodes with the smallest alpha values, where ties are broken by
+/// distance. The alpha values are kept in the scores of the candidates.
+///
+/// The distances are transformed by the penalties `pi` if given, i.e. the distance between `i`
+/// and `j` becomes `d(i, j) + pi(i) + pi(j)`. Penalties obtained from [`pi_ascent`] bring the
+/// 1-tree closer to a tour and considerably improve the quality of the candidates.
+///
+/// The operation takes O(N^2) time and O(N) space in addition to the candidates.
+///
+/// # Panics
+/// Panics if `pi` does not hold a penalty for each node of the tour.
+pub fn cand_gen_alpha<T>(tour: &T, k: usize, pi: Option<&NodeTable<Scalar>>) -> CandidateSet
+where
+    T: Tour,
+{
+    let n_nodes = tour.len();
+    let pi = penalties(n_nodes, pi);
+    let mut result = CandidateSet::with_capacity(n_nodes, k);
+
+    if n_nodes < 3 {
+        for ii in 0..n_nodes {
+            result.push((0..n_nodes).filter(|&jj| jj != ii).take(k).map(|jj| {
+                (
+                    TourNode::new(jj),
+                    CandidateScore::new(tour.distance_at(&ii, &jj), 0.),
+                )
+            }));
+        }
+        return result;
+    }
+
+    let tree = OneTree::new(tour, &pi);
+    let special = tree.special;
+    // The alpha value of an edge incident to the special node is measured against the longer of
+    // the two edges which connect the special node to the tree.
+    let special_max = cost(tour, &pi, special, tree.neighbours[0]).max(cost(
+        tour,
+        &pi,
+        special,
+        tree.neighbours[1],
+    ));
+    let alpha_special = |node: usize| {
+        if tree.neighbours.contains(&node) {
+            0.
+        } else {
+            cost(tour, &pi, special, node) - special_max
+        }
+    };
+
+    // beta[j] is the largest edge on the tree path from the current node to j. Nodes on the path
+    // from the current node to the root are marked, and all other values are derived from the
+    // parents in topological order.
+    let mut beta = vec![0.; n_nodes];
+    let mut mark = vec![usize::MAX; n_nodes];
+    let mut nearest = Vec::with_capacity(k + 1);
+
+    for ii in 0..n_nodes {
+        nearest.clear();
+
+        if ii == special {
+            for jj in (0..n_nodes).filter(|&jj| jj != ii) {
+                let c = tour.distance_at(&ii, &jj);
+                insert_bounded(&mut nearest, k, (alpha_special(jj), c, jj));
+            }
+        } else {
+            beta[ii] = Scalar::NEG_INFINITY;
+            mark[ii] = ii;
+
+            let mut node = ii;
+            while node != tree.root {
+                let dad = tree.dad[node];
+                beta[dad] = beta[node].max(cost(tour, &pi, node, dad));
+                mark[dad] = ii;
+                node = dad;
+            }
+
+            for &jj in &tree.topo {
+                if jj == ii {
+                    continue;
+                }
+
+                if mark[jj] != ii {
+                    let dad = tree.dad[jj];
+                    beta[jj] = beta[dad].max(cost(tour, &pi, jj, dad));
+                }
+
+                let alpha = if jj == special {
+                    alpha_special(ii)
+                } else {
+                    cost(tour, &pi, ii, jj) - beta[jj]
+                };
+                let c = tour.distance_at(&ii, &jj);
+                insert_bounded(&mut nearest, k, (alpha, c, jj));
+            }
+        }
+
+        result.push(
+            nearest
+                .iter()
+                .map(|&(alpha, c, jj)| (TourNode::new(jj), CandidateScore::new(c, alpha))),
+        );
+    }
+
+    result
+}
+
+/// Optimises the node penalties used by [`cand_gen_alpha`] with subgradient optimisation and
+/// returns the best penalties found.
+///
+/// The penalties maximise the lower bound of [`lower_bound`] on the length of an optimal tour,
+/// which pushes the degree of every node in the minimum 1-tree towards two. The step sizes and
+/// periods follow the scheme of LKH. Each iteration computes a minimum 1-tree in O(N^2) time, and
+/// at most `max_iterations` iterations are carried out.
+pub fn pi_ascent<T>(tour: &T, max_iterations: usize) -> NodeTable<Scalar>
+where
+    T: Tour,
+{
+    let n_nodes = tour.len();
+    let mut pi = vec![0.; n_nodes];
+    let mut result = NodeTable::new(n_nodes, 0.);
+    if n_nodes < 3 {
+        return result;
+    }
+
+    let initial_period = (n_nodes / 2).max(100);
+    let mut tree = OneTree::new(tour, &pi);
+    let mut best_w = tree.cost;
+    let mut last_v = vec![0; n_nodes];
+    let mut norm = tree.norm();
+
+    let mut step: Scalar = 1.;
+    let mut period = initial_period;
+    let mut initial_phase = true;
+    let mut iterations = 0;
+
+    while period > 0 && norm != 0 && iterations < max_iterations {
+        let mut p = 1;
+        while p <= period && norm != 0 && iterations < max_iterations {
+            for (ii, pi) in pi.iter_mut().enumerate() {
+                let v = tree.degree[ii] as i64 - 2;
+                if v != 0 {
+                    *pi += step * (7 * v + 3 * last_v[ii]) as Scalar / 10.;
+                }
+                last_v[ii] = v;
+            }
+
+            tree = OneTree::new(tour, &pi);
+            norm = tree.norm();
+            iterations += 1;
+
+            let w = tree.cost - 2. * pi.iter().sum::<Scalar>();
+            if w > best_w {
+                best_w = w;
+                for (ii, &value) in pi.iter().enumerate() {
+                    result[&tour.get(ii).unwrap()] = value;
+                }
+
+                if initial_phase {
+                    step *= 2.;
+                }
+                if p == period {
+                    period = (period * 2).min(initial_period);
+                }
+            } else if initial_phase && p > period / 2 {
+                initial_phase = false;
+                p = 0;
+                step *= 0.75;
+            }
+
+            p += 1;
+        }
+
+        period /= 2;
+        step /= 2.;
+    }
+
+    result
+}
+
+/// Returns the Held-Karp lower bound on the length of an optimal tour for the given penalties,
+/// i.e. the cost of a minimum 1-tree minus twice the sum of the penalties.
+///
+/// The operation takes O(N^2) time.
+///
+/// # Panics
+/// Panics if `pi` does not hold a penalty for each node of the tour.
+pub fn lower_bound<T>(tour: &T, pi: Option<&NodeTable<Scalar>>) -> Scalar
+where
+    T: Tour,
+{
+    let n_nodes = tour.len();
+    if n_nodes < 3 {
+        return tour.total_distance();
+    }
+
+    let pi = penalties(n_nodes, pi);
+    OneTree::new(tour, &pi).cost - 2. * pi.iter().sum::<Scalar>()
+}
+
+/// A minimum 1-tree, i.e. a minimum spanning tree in which one leaf, the special node, is
+/// connected by an additional edge to its nearest node other than its neighbour in the tree.
+struct OneTree {
+    /// The node at which the spanning tree is rooted.
+    root: usize,
+    /// The parent of each node in the spanning tree. The root is its own parent.
+    dad: Vec<usize>,
+    /// The nodes in the order they were added to the spanning tree, i.e. parents before children.
+    topo: Vec<usize>,
+    /// The leaf with the longest additional edge.
+    special: usize,
+    /// The two nodes to which the special node is connected.
+    neighbours: [usize; 2],
+    /// The number of edges incident to each node.
+    degree: Vec<usize>,
+    /// The cost of the 1-tree under the penalties.
+    cost: Scalar,
+}
+
+impl OneTree {
+    /// Computes a minimum 1-tree with Prim's algorithm in O(N^2) time. At least three nodes are
+    /// required.
+    fn new<T>(tour: &T, pi: &[Scalar]) -> Self
+    where
+        T: Tour,
+    {
+        let n_nodes = tour.len();
+        let root = 0;
+        let mut dad = vec![root; n_nodes];
+        let mut key = vec![Scalar::MAX; n_nodes];
+        let mut in_tree = vec![false; n_nodes];
+        let mut topo = Vec::with_capacity(n_nodes);
+        let mut total = 0.;
+
+        key[root] = 0.;
+        for _ in 0..n_nodes {
+            let mut u = usize::MAX;
+            for (v, &in_tree) in in_tree.iter().enumerate() {
+                if !in_tree && (u == usize::MAX || key[v] < key[u]) {
+                    u = v;
+                }
+            }
+
+            in_tree[u] = true;
+            topo.push(u);
+            total += key[u];
+
+            for v in 0..n_nodes {
+                if !in_tree[v] {
+                    let c = cost(tour, pi, u, v);
+                    if c < key[v] {
+                        key[v] = c;
+                        dad[v] = u;
+                    }
+                }
+            }
+        }
+
+        let mut degree = vec![0; n_nodes];
+        for &v in &topo[1..] {
+            degree[v] += 1;
+            degree[dad[v]] += 1;
+        }
+
+        // Among all leaves, the one whose second nearest node is farthest away becomes special.
+        let mut special = (usize::MAX, 0, Scalar::MIN);
+        for leaf in (0..n_nodes).filter(|&v| degree[v] == 1) {
+            let nbr = if leaf == root { topo[1] } else { dad[leaf] };
+            let next = (0..n_nodes)
+                .filter(|&v| v != leaf && v != nbr)
+                .map(|v| (v, cost(tour, pi, leaf, v)))
+                .fold(
+                    (usize::MAX, Scalar::MAX),
+                    |a, b| if b.1 < a.1 { b } else { a },
+                );
+
+            if next.1 > special.2 {
+                special = (leaf, next.0, next.1);
+            }
+        }
+
+        let (leaf, next, c) = special;
+        let nbr = if leaf == root { topo[1] } else { dad[leaf] };
+        degree[leaf] += 1;
+        degree[next] += 1;
+
+        Self {
+            root,
+            dad,
+            topo,
+            special: leaf,
+            neighbours: [nbr, next],
+            degree,
+            cost: total + c,
+        }
+    }
+
+    /// Returns the squared norm of the subgradient, which is zero if and only if the 1-tree is a
+    /// tour.
+    fn norm(&self) -> i64 {
+        self.degree
+            .iter()
+            .map(|&d| (d as i64 - 2) * (d as i64 - 2))
+            .sum()
+    }
+}
+
+/// Returns the distance between two nodes transformed by their penalties.
+///
+/// The nodes are ordered, so that the floating-point result does not depend on the direction.
+#[inline]
+fn cost<T>(tour: &T, pi: &[Scalar], a: usize, b: usize) -> Scalar
+where
+    T: Tour,
+{
+    let (a, b) = if a < b { (a, b) } else { (b, a) };
+    tour.distance_at(&a, &b) + pi[a] + pi[b]
+}
+
+/// Copies the penalties of all nodes, or returns zero penalties if none are given.
+///
+/// # Panics
+/// Panics if the number of penalties differs from the number of nodes.
+fn penalties(n_nodes: usize, pi: Option<&NodeTable<Scalar>>) -> Vec<Scalar> {
+    match pi {
+        Some(pi) => {
+            assert_eq!(
+                n_nodes,
+                pi.len(),
+                "Expected a penalty for each of the {} nodes, received {}.",
+                n_nodes,
+                pi.len()
+            );
+            pi.iter().copied().collect()
+        }
+        None => vec![0.; n_nodes],
+    }
+}
+
+/// Inserts an entry into a list which is sorted by alpha value and distance and holds at most
+/// `k` entries.
+#[inline]
+fn insert_bounded(
+    list: &mut Vec<(Scalar, Scalar, usize)>,
+    k: usize,
+    entry: (Scalar, Scalar, usize),
+) {
+    if list.len() == k {
+        match list.last() {
+            Some(last) if (entry.0, entry.1) < (last.0, last.1) => {
+                list.pop();
+            }
+            _ => return,
+        }
+    }
+
+    let pos = list
+        .iter()
+        .position(|other| (entry.0, entry.1) < (other.0, other.1))
+        .unwrap_or(list.len());
+    list.insert(pos, entry);
+}
@@ -3,14 +3,22 @@ use crate::{
     Scalar,
 };
 
+use super::{CandidateScore, CandidateSet};
+
 /// Generates candidates for each node by using the k-nearest-neighbour method.
 ///
-/// The operation will take O(k*N^2) time to complete.
-pub fn cand_gen_nn<T>(tour: &mut T, k: usize)
+/// Candidates are sorted by distance, and nodes at equal distance by their indices. A node
+/// receives fewer than `k` candidates only if the tour has no further nodes.
+///
+/// The operation will take O(k*N^2) time to complete. For metrics based on coordinates,
+/// [`cand_gen_kd`](super::cand_gen_kd) produces the same lists considerably faster.
+pub fn cand_gen_nn<T>(tour: &T, k: usize) -> CandidateSet
 where
     T: Tour,
 {
-    for mut base in tour.itr() {
+    let mut result = CandidateSet::with_capacity(tour.len(), k);
+
+    for base in tour.itr() {
         // Vec of candidates.
         let mut vec_c: Vec<TourNode> = vec![TourNode::default(); k];
         // Vec of distance to nearest candidates.
@@ -41,7 +49,117 @@ where
             }
         }
 
-        debug_assert_eq!(k, vec_c.len(), "{:?}", &base);
-        base.set_candidates(vec_c);
+        result.push(
+            vec_c
+                .into_iter()
+                .zip(vec_d)
+                .take(count)
+                .map(|(node, d)| (node, CandidateScore::new(d, 0.))),
+        );
     }
+
+    result
+}
+
+/// Generates candidates for each node by picking its nearest neighbours in each quadrant, or
+/// octant in three dimensions, around the node.
+///
+/// The slots are handed out in rounds, so that nodes on the border of a cluster also receive
+/// candidates outside of it: each round adds the next nearest node of every one of the `2^dim`
+/// regions around the node, nearer ones first, until `k` candidates are chosen. Each region thus
+/// contributes at most `ceil(k / 2^dim)` nodes, and if `k` is smaller than the number of regions,
+/// the nearest nodes of the `k` closest regions are chosen. Slots left by regions with too few
+/// nodes are filled with the nearest nodes which are not yet in the list. The candidates are
+/// sorted by distance. For metrics without coordinates, the method falls back to the nearest
+/// neighbours.
+///
+/// The operation will take O(k*N^2) time to complete.
+pub fn cand_gen_quadrant<T>(tour: &T, k: usize) -> CandidateSet
+where
+    T: Tour,
+{
+    let n_nodes = tour.len();
+    let n_regions = match tour.get(0) {
+        Some(node) => 1 << tour.coords(&node).len(),
+        None => 1,
+    };
+    let per_region = k.div_ceil(n_regions);
+    let mut result = CandidateSet::with_capacity(n_nodes, k);
+
+    let mut regions = vec![Vec::with_capacity(per_region + 1); n_regions];
+    let mut nearest = Vec::with_capacity(k + 1);
+    let mut round: Vec<(Scalar, usize)> = Vec::with_capacity(n_regions);
+    let mut list = Vec::with_capacity(k);
+
+    for base in tour.itr() {
+        regions.iter_mut().for_each(|region| region.clear());
+        nearest.clear();
+        let origin = tour.coords(&base);
+
+        for targ in tour.itr().filter(|targ| *targ != base) {
+            let d = tour.distance(&base, &targ);
+            let region =
+                tour.coords(&targ)
+                    .iter()
+                    .zip(origin)
+                    .enumerate()
+                    .fold(
+                        0,
+                        |acc, (axis, (x, o))| {
+                            if x >= o {
+                                acc | (1 << axis)
+                            } else {
+                                acc
+                            }
+                        },
+                    );
+
+            insert_nearest(&mut regions[region], per_region, (d, targ.index()));
+            insert_nearest(&mut nearest, k, (d, targ.index()));
+        }
+
+        list.clear();
+        for rank in 0..per_region {
+            round.clear();
+            round.extend(regions.iter().filter_map(|region| region.get(rank)));
+            round.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            list.extend(round.iter().take(k - list.len()));
+        }
+        for entry in &nearest {
+            if list.len() == k {
+                break;
+            }
+            if !list.iter().any(|(_, targ)| *targ == entry.1) {
+                list.push(*entry);
+            }
+        }
+        list.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+        result.push(
+            list.iter()
+                .map(|&(d, targ)| (TourNode::new(targ), CandidateScore::new(d, 0.))),
+        );
+    }
+
+    result
+}
+
+/// Inserts a node into a list which is sorted by distance and index, and holds at most `k`
+/// entries.
+#[inline]
+pub(super) fn insert_nearest(list: &mut Vec<(Scalar, usize)>, k: usize, entry: (Scalar, usize)) {
+    if list.len() == k {
+        match list.last() {
+            Some(last) if entry < *last => {
+                list.pop();
+            }
+            _ => return,
+        }
+    }
+
+    let pos = list
+        .iter()
+        .position(|other| entry < *other)
+        .unwrap_or(list.len());
+    list.insert(pos, entry);
 }
@@ -0,0 +1,307 @@
+use std::io::{BufRead, Write};
+
+use crate::{
+    input::{InputError, RecordError, RecordErrorKind},
+    tour::{Tour, TourNode},
+    Scalar,
+};
+
+use super::{CandidateScore, CandidateSet, NodeTable};
+
+/// Writes a candidate set in the format of the `CANDIDATE_FILE` of LKH.
+///
+/// The file starts with the number of nodes, followed by a line for each node with its id, the
+/// id of its parent in the minimum spanning tree, its number of candidates and the id and alpha
+/// value of each candidate. Ids are 1-based, and the file ends with ```-1``` and ```EOF```.
+///
+/// LKH stores costs as integers scaled by its `PRECISION` parameter, which is 100 by default.
+/// The alpha values are therefore multiplied by `precision` and rounded. Since the set does not
+/// keep the spanning tree, every node is written without a parent.
+///
+/// An error of kind [`InvalidInput`](std::io::ErrorKind::InvalidInput) is returned if
+/// `precision` is zero, negative or not finite.
+pub fn write_candidates<W>(
+    mut writer: W,
+    cands: &CandidateSet,
+    precision: Scalar,
+) -> std::io::Result<()>
+where
+    W: Write,
+{
+    check_precision(precision).map_err(invalid_input)?;
+    writeln!(writer, "{}", cands.len())?;
+    for idx in 0..cands.len() {
+        let node = TourNode::new(idx);
+        let list = cands.get(&node);
+        write!(writer, "{} 0 {}", idx + 1, list.len())?;
+        for (cand, score) in list.iter().zip(cands.scores(&node)) {
+            write!(
+                writer,
+                " {} {}",
+                cand.index() + 1,
+                scale(score.alpha, precision)
+            )?;
+        }
+        writeln!(writer)?;
+    }
+    writeln!(writer, "-1\nEOF")?;
+    writer.flush()
+}
+
+/// Reads a candidate set in the format of the `CANDIDATE_FILE` of LKH, as described in
+/// [`write_candidates`].
+///
+/// The alpha values are divided by `precision`, and the distances are computed from the tour.
+/// The parents in the minimum spanning tree are ignored. Reading stops at ```-1```, ```EOF```
+/// or the end of the input, and empty lines are skipped.
+///
+/// The file must list each node of the tour exactly once, otherwise
+/// [`InputError::NodesMismatched`] is returned. A line is invalid if it announces more
+/// candidates than there are other nodes or than it holds, or if a candidate is out of range,
+/// the node itself or listed twice. All invalid lines are collected and reported together in
+/// [`InputError::InvalidRecords`]. A `precision` which is zero, negative or not
+/// finite is rejected with [`InputError::InvalidPrecision`].
+pub fn read_candidates<T, R>(
+    tour: &T,
+    reader: R,
+    precision: Scalar,
+) -> Result<CandidateSet, InputError>
+where
+    T: Tour,
+    R: BufRead,
+{
+    check_precision(precision)?;
+    let mut lines = Lines::new(reader, tour.len())?;
+    let mut lists = vec![None; tour.len()];
+    let mut errors = Vec::new();
+
+    while let Some((line, fields)) = lines.next_record()? {
+        let record = unseen(&lines, &fields, &lists).and_then(|base| {
+            let count = integer(&fields, 2)?;
+            if count >= lines.n_nodes {
+                return Err(RecordErrorKind::InvalidCount {
+                    column: 2,
+                    value: fields[2].clone(),
+                });
+            }
+            if fields.len() < 3 + 2 * count {
+                return Err(RecordErrorKind::MissingField {
+                    column: fields.len(),
+                });
+            }
+
+            let mut list: Vec<(TourNode, CandidateScore)> = Vec::with_capacity(count);
+            for pos in 0..count {
+                let column = 3 + 2 * pos;
+                let cand = lines.node(&fields, column)?;
+                if cand == base {
+                    return Err(RecordErrorKind::SelfCandidate(fields[column].clone()));
+                }
+                if list.iter().any(|(node, _)| node.index() == cand) {
+                    return Err(RecordErrorKind::DuplicateId(fields[column].clone()));
+                }
+                let alpha = number(&fields, column + 1)? / precision;
+                let score = CandidateScore::new(tour.distance_at(&base, &cand), alpha);
+                list.push((TourNode::new(cand), score));
+            }
+            Ok((base, list))
+        });
+
+        match record {
+            Ok((base, list)) => lists[base] = Some(list),
+            Err(kind) => errors.push(RecordError::new(line, kind)),
+        }
+    }
+
+    lines.finish(errors, &lists)?;
+    Ok(lists.into_iter().map(Option::unwrap).collect())
+}
+
+/// Writes the penalties of the nodes in the format of the `PI_FILE` of LKH.
+///
+/// The file starts with the number of nodes, followed by a line for each node with its 1-based
+/// id and its penalty, and ends with ```-1``` and ```EOF```. Like the alpha values in
+/// [`write_candidates`], the penalties are multiplied by `precision` and rounded, and an invalid
+/// `precision` is rejected.
+pub fn write_pi<W>(mut writer: W, pi: &NodeTable<Scalar>, precision: Scalar) -> std::io::Result<()>
+where
+    W: Write,
+{
+    check_precision(precision).map_err(invalid_input)?;
+    writeln!(writer, "{}", pi.len())?;
+    for (idx, value) in pi.iter().enumerate() {
+        writeln!(writer, "{} {}", idx + 1, scale(*value, precision))?;
+    }
+    writeln!(writer, "-1\nEOF")?;
+    writer.flush()
+}
+
+/// Reads the penalties of `n_nodes` nodes in the format of the `PI_FILE` of LKH, as described in
+/// [`write_pi`]. The penalties are divided by `precision`.
+///
+/// Errors are handled as in [`read_candidates`].
+pub fn read_pi<R>(
+    reader: R,
+    n_nodes: usize,
+    precision: Scalar,
+) -> Result<NodeTable<Scalar>, InputError>
+where
+    R: BufRead,
+{
+    check_precision(precision)?;
+    let mut lines = Lines::new(reader, n_nodes)?;
+    let mut values = vec![None; n_nodes];
+    let mut errors = Vec::new();
+
+    while let Some((line, fields)) = lines.next_record()? {
+        let record = unseen(&lines, &fields, &values)
+            .and_then(|node| Ok((node, number(&fields, 1)? / precision)));
+
+        match record {
+            Ok((node, value)) => values[node] = Some(value),
+            Err(kind) => errors.push(RecordError::new(line, kind)),
+        }
+    }
+
+    lines.finish(errors, &values)?;
+    let mut result = NodeTable::new(n_nodes, 0.);
+    for (idx, value) in values.into_iter().enumerate() {
+        result[&TourNode::new(idx)] = value.unwrap();
+    }
+    Ok(result)
+}
+
+/// Splits an LKH file into the whitespace-separated fields of its lines, after checking that its
+/// first line holds the expected number of nodes.
+struct Lines<R> {
+    lines: std::iter::Enumerate<std::io::Lines<R>>,
+    n_nodes: usize,
+}
+
+impl<R> Lines<R>
+where
+    R: BufRead,
+{
+    fn new(reader: R, n_nodes: usize) -> Result<Self, InputError> {
+        let mut result = Self {
+            lines: reader.lines().enumerate(),
+            n_nodes,
+        };
+
+        let (line, fields) = match result.next_record()? {
+            Some(record) => record,
+            None => (1, Vec::new()),
+        };
+        let dimension = integer(&fields, 0)
+            .map_err(|kind| InputError::InvalidRecords(vec![RecordError::new(line, kind)]))?;
+        if dimension != n_nodes {
+            return Err(InputError::NodesMismatched {
+                expected: n_nodes,
+                received: dimension,
+            });
+        }
+
+        Ok(result)
+    }
+
+    /// Returns the next non-empty line with its 1-based number, or ```None``` at the end of the
+    /// records.
+    fn next_record(&mut self) -> Result<Option<(usize, Vec<String>)>, InputError> {
+        for (idx, line) in &mut self.lines {
+            let line = line?;
+            let fields: Vec<_> = line.split_whitespace().map(str::to_string).collect();
+            match fields.first().map(String::as_str) {
+                None => continue,
+                Some("-1") | Some("EOF") => return Ok(None),
+                Some(_) => return Ok(Some((idx + 1, fields))),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Parses the 1-based node id at the given column into an index.
+    fn node(&self, fields: &[String], column: usize) -> Result<usize, RecordErrorKind> {
+        let id = integer(fields, column)?;
+        if id == 0 || id > self.n_nodes {
+            return Err(RecordErrorKind::InvalidNodeId(fields[column].clone()));
+        }
+        Ok(id - 1)
+    }
+
+    /// Reports the invalid lines, or the number of nodes if some node was not listed.
+    fn finish<V>(self, errors: Vec<RecordError>, values: &[Option<V>]) -> Result<(), InputError> {
+        if !errors.is_empty() {
+            return Err(InputError::InvalidRecords(errors));
+        }
+
+        let received = values.iter().filter(|value| value.is_some()).count();
+        if received != self.n_nodes {
+            return Err(InputError::NodesMismatched {
+                expected: self.n_nodes,
+                received,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Parses the node id at the start of a line, which must not have been read before.
+fn unseen<R, V>(
+    lines: &Lines<R>,
+    fields: &[String],
+    seen: &[Option<V>],
+) -> Result<usize, RecordErrorKind>
+where
+    R: BufRead,
+{
+    let node = lines.node(fields, 0)?;
+    if seen[node].is_some() {
+        return Err(RecordErrorKind::DuplicateId(fields[0].clone()));
+    }
+    Ok(node)
+}
+
+fn field(fields: &[String], column: usize) -> Result<&str, RecordErrorKind> {
+    fields
+        .get(column)
+        .map(String::as_str)
+        .ok_or(RecordErrorKind::MissingField { column })
+}
+
+fn integer(fields: &[String], column: usize) -> Result<usize, RecordErrorKind> {
+    let value = field(fields, column)?;
+    value.parse().map_err(|_| RecordErrorKind::InvalidNumber {
+        column,
+        value: value.to_string(),
+    })
+}
+
+fn number(fields: &[String], column: usize) -> Result<Scalar, RecordErrorKind> {
+    let value = field(fields, column)?;
+    match value.parse::<Scalar>() {
+        Ok(x) if x.is_finite() => Ok(x),
+        Ok(_) => Err(RecordErrorKind::NonFinite { column }),
+        Err(_) => Err(RecordErrorKind::InvalidNumber {
+            column,
+            value: value.to_string(),
+        }),
+    }
+}
+
+/// Checks that `precision` is a positive finite number.
+fn check_precision(precision: Scalar) -> Result<(), InputError> {
+    if precision.is_finite() && precision > 0. {
+        Ok(())
+    } else {
+        Err(InputError::InvalidPrecision(precision))
+    }
+}
+
+fn invalid_input(err: InputError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, err.to_string())
+}
+
+#[inline]
+fn scale(value: Scalar, precision: Scalar) -> i64 {
+    (value * precision).round() as i64
+}
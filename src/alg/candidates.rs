@@ -0,0 +1,261 @@
+use std::iter::FromIterator;
+
+use crate::{
+    tour::{Tour, TourNode, TourOrder},
+    Scalar,
+};
+
+/// The scores of the edge from a node to one of its candidates.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CandidateScore {
+    /// The distance between both nodes.
+    pub distance: Scalar,
+    /// The alpha value of the edge, which is zero if it was not computed.
+    pub alpha: Scalar,
+}
+
+impl CandidateScore {
+    pub fn new(distance: Scalar, alpha: Scalar) -> Self {
+        Self { distance, alpha }
+    }
+}
+
+/// The candidate lists of all nodes of a tour, stored in compressed sparse row (CSR) layout.
+///
+/// The lists are concatenated into a single vector, and the list of the node with index ```i```
+/// spans from ```offsets[i]``` to ```offsets[i + 1]```. Lists are pushed in the order of the
+/// nodes' indices. The scores of the candidates are kept in a parallel vector.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CandidateSet {
+    offsets: Vec<u32>,
+    targets: Vec<TourNode>,
+    scores: Vec<CandidateScore>,
+}
+
+impl CandidateSet {
+    /// Creates candidate lists without any node.
+    pub fn new() -> Self {
+        Self::with_capacity(0, 0)
+    }
+
+    /// Creates candidate lists with room for `n_nodes` lists of `k` candidates each.
+    pub fn with_capacity(n_nodes: usize, k: usize) -> Self {
+        let mut offsets = Vec::with_capacity(n_nodes + 1);
+        offsets.push(0);
+
+        Self {
+            offsets,
+            targets: Vec::with_capacity(n_nodes * k),
+            scores: Vec::with_capacity(n_nodes * k),
+        }
+    }
+
+    /// Appends the candidate list of the next node.
+    pub fn push<I>(&mut self, candidates: I)
+    where
+        I: IntoIterator<Item = (TourNode, CandidateScore)>,
+    {
+        for (node, score) in candidates {
+            self.targets.push(node);
+            self.scores.push(score);
+        }
+        self.offsets.push(self.targets.len() as u32);
+    }
+
+    /// Returns the candidates of a node, or an empty slice if the node has no list.
+    #[inline]
+    pub fn get(&self, node: &TourNode) -> &[TourNode] {
+        match self.range(node) {
+            Some((start, end)) => &self.targets[start..end],
+            None => &[],
+        }
+    }
+
+    /// Returns the scores of the candidates of a node, in the same order as
+    /// [`get`](Self::get).
+    #[inline]
+    pub fn scores(&self, node: &TourNode) -> &[CandidateScore] {
+        match self.range(node) {
+            Some((start, end)) => &self.scores[start..end],
+            None => &[],
+        }
+    }
+
+    /// Returns ```true``` if `cand` is a candidate of `node`.
+    #[inline]
+    pub fn contains(&self, node: &TourNode, cand: &TourNode) -> bool {
+        self.get(node).contains(cand)
+    }
+
+    /// Returns the number of nodes which have a candidate list.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns ```true``` if no node has a candidate list.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the total number of candidates over all nodes.
+    #[inline]
+    pub fn n_candidates(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Returns the iterator over the candidate lists, in the order of the nodes' indices.
+    pub fn iter(&self) -> impl Iterator<Item = &[TourNode]> + '_ {
+        self.offsets
+            .windows(2)
+            .map(move |w| &self.targets[w[0] as usize..w[1] as usize])
+    }
+
+    /// Returns the union of both candidate sets for each node.
+    ///
+    /// The candidates of `self` keep their order and scores, and are followed by the candidates
+    /// of `other` which are not already in the list.
+    pub fn union(&self, other: &CandidateSet) -> CandidateSet {
+        let mut lists = self.lists(self.len().max(other.len()));
+        for (idx, list) in lists.iter_mut().enumerate() {
+            for (cand, score) in other.entries(&TourNode::new(idx)) {
+                insert_missing(list, cand, score);
+            }
+        }
+
+        lists.into_iter().collect()
+    }
+
+    /// Returns the candidates of `self` which are also candidates of the same node in `other`,
+    /// keeping their order and scores.
+    pub fn intersect(&self, other: &CandidateSet) -> CandidateSet {
+        let mut lists = self.lists(self.len());
+        for (idx, list) in lists.iter_mut().enumerate() {
+            let node = TourNode::new(idx);
+            list.retain(|(cand, _)| other.contains(&node, cand));
+        }
+
+        lists.into_iter().collect()
+    }
+
+    /// Makes the candidate set symmetric, i.e. whenever `b` is a candidate of `a`, `a` also
+    /// becomes a candidate of `b` with the same scores.
+    ///
+    /// Added candidates are appended to the lists. Use [`sort`](Self::sort) to rank them.
+    pub fn make_symmetric(&mut self) {
+        let n_nodes = self
+            .targets
+            .iter()
+            .fold(self.len(), |acc, cand| acc.max(cand.index() + 1));
+        let mut lists = self.lists(n_nodes);
+
+        for idx in 0..self.len() {
+            let node = TourNode::new(idx);
+            for (cand, score) in self.entries(&node) {
+                insert_missing(&mut lists[cand.index()], node, score);
+            }
+        }
+
+        *self = lists.into_iter().collect();
+    }
+
+    /// Adds the edges of a tour order to the candidate lists of both of their end nodes, unless
+    /// they are already in the lists.
+    ///
+    /// The edges belong to a known good tour and are therefore added with an alpha value of
+    /// zero. Use [`sort`](Self::sort) to rank them.
+    pub fn add_tour<T>(&mut self, tour: &T, order: &TourOrder)
+    where
+        T: Tour,
+    {
+        let n_nodes = order
+            .order()
+            .iter()
+            .fold(self.len(), |acc, &idx| acc.max(idx + 1));
+        let mut lists = self.lists(n_nodes);
+
+        for (a, b) in order.edges().filter(|(a, b)| a != b) {
+            let score = CandidateScore::new(tour.distance_at(&a, &b), 0.);
+            insert_missing(&mut lists[a], TourNode::new(b), score);
+            insert_missing(&mut lists[b], TourNode::new(a), score);
+        }
+
+        *self = lists.into_iter().collect();
+    }
+
+    /// Ranks the candidates of each node by their alpha values, then by their distances and
+    /// finally by their indices. Scores are compared by their total order, so that NaN values
+    /// do not cause a panic.
+    pub fn sort(&mut self) {
+        let mut lists = self.lists(self.len());
+        for list in lists.iter_mut() {
+            list.sort_by(|(a, sa), (b, sb)| {
+                sa.alpha
+                    .total_cmp(&sb.alpha)
+                    .then(sa.distance.total_cmp(&sb.distance))
+                    .then(a.index().cmp(&b.index()))
+            });
+        }
+
+        *self = lists.into_iter().collect();
+    }
+
+    /// Keeps at most the first `k` candidates of each node.
+    pub fn truncate(&mut self, k: usize) {
+        let mut lists = self.lists(self.len());
+        lists.iter_mut().for_each(|list| list.truncate(k));
+        *self = lists.into_iter().collect();
+    }
+
+    #[inline]
+    fn range(&self, node: &TourNode) -> Option<(usize, usize)> {
+        let idx = node.index();
+        if idx + 1 < self.offsets.len() {
+            Some((self.offsets[idx] as usize, self.offsets[idx + 1] as usize))
+        } else {
+            None
+        }
+    }
+
+    fn entries(&self, node: &TourNode) -> impl Iterator<Item = (TourNode, CandidateScore)> + '_ {
+        self.get(node)
+            .iter()
+            .copied()
+            .zip(self.scores(node).iter().copied())
+    }
+
+    /// Returns the candidate lists of the first `n_nodes` nodes as separate vectors.
+    fn lists(&self, n_nodes: usize) -> Vec<Vec<(TourNode, CandidateScore)>> {
+        (0..n_nodes)
+            .map(|idx| self.entries(&TourNode::new(idx)).collect())
+            .collect()
+    }
+}
+
+impl Default for CandidateSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FromIterator<Vec<(TourNode, CandidateScore)>> for CandidateSet {
+    fn from_iter<I: IntoIterator<Item = Vec<(TourNode, CandidateScore)>>>(iter: I) -> Self {
+        let mut result = Self::new();
+        for list in iter {
+            result.push(list);
+        }
+        result
+    }
+}
+
+#[inline]
+fn insert_missing(
+    list: &mut Vec<(TourNode, CandidateScore)>,
+    cand: TourNode,
+    score: CandidateScore,
+) {
+    if !list.iter().any(|(other, _)| *other == cand) {
+        list.push((cand, score));
+    }
+}
@@ -0,0 +1,368 @@
+use crate::{data::DataStore, tour::TourNode, Scalar};
+
+use super::{CandidateScore, CandidateSet};
+
+/// Marks the vertex at infinity, which closes the triangulation around its convex hull.
+const GHOST: usize = usize::MAX;
+/// Marks a missing triangle.
+const NONE: usize = usize::MAX;
+
+/// Generates candidates for each node from the Delaunay triangulation of the nodes' coordinates.
+///
+/// Each node receives its neighbours in the triangulation, sorted by distance. If `second_level`
+/// is set, the lists are extended with the neighbours of the neighbours, which are again sorted
+/// by distance and follow the direct neighbours. Lists are capped at `k` candidates if given.
+///
+/// Nodes with identical coordinates are neighbours of each other and share the neighbours of the
+/// first of them. If all nodes lie on a line, the neighbours of a node are the nodes next to it
+/// on the line.
+///
+/// The triangulation is built incrementally and typically takes O(N log N) time.
+///
+/// # Panics
+/// Panics if the metric of the store is not two-dimensional, or if a coordinate is infinite or
+/// NaN.
+pub fn cand_gen_delaunay<M>(
+    store: &DataStore<M>,
+    k: Option<usize>,
+    second_level: bool,
+) -> CandidateSet {
+    assert_eq!(
+        2,
+        store.dim(),
+        "Delaunay candidates require two-dimensional coordinates."
+    );
+
+    let n_nodes = store.len();
+    let points: Vec<_> = (0..n_nodes)
+        .map(|idx| {
+            let coords = store.coords(&idx);
+            [coords[0], coords[1]]
+        })
+        .collect();
+    assert!(
+        points.iter().flatten().all(|x| x.is_finite()),
+        "Delaunay candidates require finite coordinates."
+    );
+    let neighbours = Triangulation::new(&points).neighbours();
+
+    let cap = k.unwrap_or(usize::MAX);
+    let mut result = CandidateSet::with_capacity(n_nodes, cap.min(8));
+    let mut seen = vec![usize::MAX; n_nodes];
+
+    for base in 0..n_nodes {
+        let by_distance = |a: &usize, b: &usize| {
+            store
+                .cost(&base, a)
+                .total_cmp(&store.cost(&base, b))
+                .then(a.cmp(b))
+        };
+
+        seen[base] = base;
+        let mut list = neighbours[base].clone();
+        list.sort_by(by_distance);
+        list.iter().for_each(|&node| seen[node] = base);
+
+        if second_level && list.len() < cap {
+            let mut extra = Vec::new();
+            for &node in &neighbours[base] {
+                for &next in &neighbours[node] {
+                    if seen[next] != base {
+                        seen[next] = base;
+                        extra.push(next);
+                    }
+                }
+            }
+            extra.sort_by(by_distance);
+            list.extend(extra);
+        }
+
+        list.truncate(cap);
+        result.push(list.into_iter().map(|node| {
+            let score = CandidateScore::new(store.cost(&base, &node), 0.);
+            (TourNode::new(node), score)
+        }));
+    }
+
+    result
+}
+
+/// A Delaunay triangulation built with the Bowyer-Watson algorithm.
+///
+/// The triangulation is closed by ghost triangles, which connect each edge of the convex hull
+/// with a vertex at infinity. This avoids the precision issues of an enclosing super triangle.
+/// Triangles list their vertices counter-clockwise, and the neighbour at position `i` shares the
+/// edge opposite to the vertex at position `i`.
+struct Triangulation<'a> {
+    points: &'a [[Scalar; 2]],
+    vertices: Vec<[usize; 3]>,
+    adjacent: Vec<[usize; 3]>,
+    alive: Vec<bool>,
+    /// Nodes with the same coordinates as an inserted node, together with that node.
+    duplicates: Vec<(usize, usize)>,
+    /// Nodes which are collinear with all other nodes, in the order along their line.
+    line: Vec<usize>,
+}
+
+impl<'a> Triangulation<'a> {
+    fn new(points: &'a [[Scalar; 2]]) -> Self {
+        let mut result = Self {
+            points,
+            vertices: Vec::new(),
+            adjacent: Vec::new(),
+            alive: Vec::new(),
+            duplicates: Vec::new(),
+            line: Vec::new(),
+        };
+
+        // Inserting nodes in sorted order keeps the walks to the next node short.
+        let mut order: Vec<_> = (0..points.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (p, q) = (points[a], points[b]);
+            p[0].total_cmp(&q[0]).then(p[1].total_cmp(&q[1]))
+        });
+        order.dedup_by(|b, a| {
+            let same = points[*a] == points[*b];
+            if same {
+                result.duplicates.push((*b, *a));
+            }
+            same
+        });
+
+        let apex = (2..order.len())
+            .find(|&idx| orient(points[order[0]], points[order[1]], points[order[idx]]) != 0.);
+        let apex = match apex {
+            Some(apex) => apex,
+            None => {
+                result.line = order;
+                return result;
+            }
+        };
+
+        let (a, b, c) = (order[0], order[1], order[apex]);
+        if orient(points[a], points[b], points[c]) > 0. {
+            result.init(a, b, c);
+        } else {
+            result.init(a, c, b);
+        }
+
+        let mut last = 0;
+        for (idx, &node) in order.iter().enumerate().skip(2) {
+            if idx != apex {
+                last = result.insert(node, last);
+            }
+        }
+
+        result
+    }
+
+    /// Creates the first triangle from three counter-clockwise nodes, enclosed by three ghosts.
+    fn init(&mut self, a: usize, b: usize, c: usize) {
+        self.vertices = vec![[a, b, c], [b, a, GHOST], [c, b, GHOST], [a, c, GHOST]];
+        self.adjacent = vec![[2, 3, 1], [3, 2, 0], [1, 3, 0], [2, 1, 0]];
+        self.alive = vec![true; 4];
+    }
+
+    /// Inserts a node by replacing all triangles whose circumcircle contains it, starting the
+    /// search at the given triangle. Returns a triangle incident to the node.
+    fn insert(&mut self, node: usize, start: usize) -> usize {
+        let first = match self.locate(node, start) {
+            Ok(tri) => tri,
+            Err(duplicate) => {
+                self.duplicates.push((node, duplicate));
+                return start;
+            }
+        };
+
+        // Collect the cavity of conflicting triangles and its boundary.
+        let mut cavity = vec![first];
+        let mut boundary = Vec::new();
+        self.alive[first] = false;
+        let mut idx = 0;
+        while idx < cavity.len() {
+            let tri = cavity[idx];
+            for pos in 0..3 {
+                let next = self.adjacent[tri][pos];
+                if !self.alive[next] {
+                    continue;
+                }
+
+                if self.conflicts(next, node) {
+                    self.alive[next] = false;
+                    cavity.push(next);
+                } else {
+                    let v = self.vertices[tri];
+                    boundary.push((v[(pos + 1) % 3], v[(pos + 2) % 3], next));
+                }
+            }
+            idx += 1;
+        }
+
+        // Connect each boundary edge to the new node, reusing the slots of the cavity.
+        let mut slots = cavity.into_iter();
+        let mut created = Vec::with_capacity(boundary.len());
+        for &(u, v, outer) in &boundary {
+            let tri = match slots.next() {
+                Some(slot) => slot,
+                None => {
+                    self.vertices.push([0; 3]);
+                    self.adjacent.push([NONE; 3]);
+                    self.alive.push(false);
+                    self.vertices.len() - 1
+                }
+            };
+
+            self.vertices[tri] = [u, v, node];
+            self.adjacent[tri] = [NONE, NONE, outer];
+            self.alive[tri] = true;
+
+            let pos = (0..3)
+                .find(|&pos| {
+                    let w = self.vertices[outer];
+                    w[(pos + 1) % 3] == v && w[(pos + 2) % 3] == u
+                })
+                .unwrap();
+            self.adjacent[outer][pos] = tri;
+            created.push(tri);
+        }
+
+        // Link the new triangles around the node: the edge (v, node) of the triangle (u, v) is
+        // shared with the triangle starting at v.
+        for &tri in &created {
+            let [u, v, _] = self.vertices[tri];
+            for &other in &created {
+                let [s, t, _] = self.vertices[other];
+                if s == v {
+                    self.adjacent[tri][0] = other;
+                }
+                if t == u {
+                    self.adjacent[tri][1] = other;
+                }
+            }
+        }
+
+        created
+            .into_iter()
+            .find(|&tri| !self.vertices[tri].contains(&GHOST))
+            .unwrap_or(start)
+    }
+
+    /// Returns a triangle which conflicts with the node, or the node with identical coordinates.
+    fn locate(&self, node: usize, start: usize) -> Result<usize, usize> {
+        let p = self.points[node];
+        let mut tri = if self.alive[start] { start } else { 0 };
+        let mut steps = 0;
+
+        // Walk towards the node along the triangles it lies beyond of.
+        'walk: while steps < self.vertices.len() && !self.vertices[tri].contains(&GHOST) {
+            let v = self.vertices[tri];
+            if let Some(&same) = v.iter().find(|&&w| self.points[w] == p) {
+                return Err(same);
+            }
+
+            for pos in 0..3 {
+                let (a, b) = (v[(pos + 1) % 3], v[(pos + 2) % 3]);
+                if orient(self.points[a], self.points[b], p) < 0. {
+                    tri = self.adjacent[tri][pos];
+                    steps += 1;
+                    continue 'walk;
+                }
+            }
+
+            return Ok(tri);
+        }
+
+        if self.alive[tri] && self.conflicts(tri, node) {
+            return Ok(tri);
+        }
+
+        // The walk failed due to rounding, so fall back to a scan over all triangles.
+        let alive = (0..self.vertices.len()).filter(|&tri| self.alive[tri]);
+        for tri in alive.clone() {
+            let v = self.vertices[tri];
+            if let Some(&same) = v.iter().find(|&&w| w != GHOST && self.points[w] == p) {
+                return Err(same);
+            }
+        }
+        Ok(alive
+            .into_iter()
+            .find(|&tri| self.conflicts(tri, node))
+            .unwrap())
+    }
+
+    /// Returns ```true``` if the node lies inside the circumcircle of the triangle. The
+    /// circumcircle of a ghost triangle is the open half-plane beyond its hull edge, together
+    /// with the hull edge itself.
+    fn conflicts(&self, tri: usize, node: usize) -> bool {
+        let v = self.vertices[tri];
+        let p = self.points[node];
+
+        match v.iter().position(|&w| w == GHOST) {
+            Some(pos) => {
+                let (a, b) = (self.points[v[(pos + 1) % 3]], self.points[v[(pos + 2) % 3]]);
+                let side = orient(a, b, p);
+                side > 0.
+                    || (side == 0.
+                        && (a[0] - p[0]) * (b[0] - p[0]) + (a[1] - p[1]) * (b[1] - p[1]) < 0.)
+            }
+            None => in_circle(self.points[v[0]], self.points[v[1]], self.points[v[2]], p) > 0.,
+        }
+    }
+
+    /// Returns the neighbours of each node in the triangulation.
+    fn neighbours(&self) -> Vec<Vec<usize>> {
+        let mut result = vec![Vec::new(); self.points.len()];
+        let link = |result: &mut Vec<Vec<usize>>, a: usize, b: usize| {
+            if !result[a].contains(&b) {
+                result[a].push(b);
+                result[b].push(a);
+            }
+        };
+
+        for pair in self.line.windows(2) {
+            link(&mut result, pair[0], pair[1]);
+        }
+
+        for (tri, v) in self.vertices.iter().enumerate() {
+            if self.alive[tri] {
+                for pos in 0..3 {
+                    let (a, b) = (v[pos], v[(pos + 1) % 3]);
+                    if a != GHOST && b != GHOST {
+                        link(&mut result, a, b);
+                    }
+                }
+            }
+        }
+
+        // Duplicates are linked after the original, so that they reach its neighbours.
+        for &(duplicate, original) in &self.duplicates {
+            let others = result[original].clone();
+            for other in others {
+                link(&mut result, duplicate, other);
+            }
+            link(&mut result, duplicate, original);
+        }
+
+        result
+    }
+}
+
+/// Returns a positive value if the points turn counter-clockwise, a negative value if they turn
+/// clockwise and zero if they are collinear.
+#[inline]
+fn orient(a: [Scalar; 2], b: [Scalar; 2], c: [Scalar; 2]) -> Scalar {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Returns a positive value if `p` lies inside the circumcircle of the counter-clockwise
+/// triangle `(a, b, c)`.
+#[inline]
+fn in_circle(a: [Scalar; 2], b: [Scalar; 2], c: [Scalar; 2], p: [Scalar; 2]) -> Scalar {
+    let (adx, ady) = (a[0] - p[0], a[1] - p[1]);
+    let (bdx, bdy) = (b[0] - p[0], b[1] - p[1]);
+    let (cdx, cdy) = (c[0] - p[0], c[1] - p[1]);
+
+    (adx * adx + ady * ady) * (bdx * cdy - cdx * bdy)
+        + (bdx * bdx + bdy * bdy) * (cdx * ady - adx * cdy)
+        + (cdx * cdx + cdy * cdy) * (adx * bdy - bdx * ady)
+}
@@ -0,0 +1,179 @@
+use std::thread;
+
+use crate::{
+    data::{DataStore, Metric},
+    tour::TourNode,
+    Scalar,
+};
+
+use super::{cand_gen::insert_nearest, CandidateScore, CandidateSet};
+
+/// Generates candidates for each node by using the k-nearest-neighbour method with a k-d tree over
+/// the nodes' coordinates.
+///
+/// The lists are identical to those of [`cand_gen_nn`](super::cand_gen_nn), i.e. sorted by
+/// distance, and nodes at equal distance by their indices. The base nodes are split evenly over
+/// `n_threads` threads, each of which searches the shared tree.
+///
+/// Building the tree takes O(N log N) time, and each search typically takes O(k log N) time.
+/// Subtrees are only pruned for metrics which grow with the difference in each coordinate, i.e.
+/// the Euclidean, Manhattan, maximum, rounded-up Euclidean and pseudo-Euclidean metrics. For all
+/// other metrics, the search degrades to a scan over all nodes.
+pub fn cand_gen_kd<M>(store: &DataStore<M>, k: usize, n_threads: usize) -> CandidateSet
+where
+    M: Sync,
+{
+    let n_nodes = store.len();
+    let tree = KdTree::new(store);
+    let chunk = n_nodes.div_ceil(n_threads.max(1)).max(1);
+
+    let lists: Vec<Vec<_>> = if chunk >= n_nodes {
+        (0..n_nodes).map(|base| tree.nearest(base, k)).collect()
+    } else {
+        thread::scope(|scope| {
+            let tree = &tree;
+            let handles: Vec<_> = (0..n_nodes)
+                .step_by(chunk)
+                .map(|start| {
+                    let end = (start + chunk).min(n_nodes);
+                    scope.spawn(move || {
+                        (start..end)
+                            .map(|base| tree.nearest(base, k))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        })
+    };
+
+    let mut result = CandidateSet::with_capacity(n_nodes, k);
+    for list in lists {
+        result.push(list);
+    }
+    result
+}
+
+/// A k-d tree stored implicitly in a permutation of the nodes' indices.
+///
+/// The node in the middle of a range splits the range along its axis: nodes before it do not
+/// have a greater coordinate on that axis, and nodes after it do not have a smaller one.
+struct KdTree<'s, M> {
+    store: &'s DataStore<M>,
+    nodes: Vec<usize>,
+    axes: Vec<usize>,
+    prune: bool,
+}
+
+impl<'s, M> KdTree<'s, M> {
+    fn new(store: &'s DataStore<M>) -> Self {
+        let n_nodes = store.len();
+        let prune = matches!(
+            store.metric(),
+            Metric::Euc2d
+                | Metric::Euc3d
+                | Metric::Man2d
+                | Metric::Man3d
+                | Metric::Max2d
+                | Metric::Max3d
+                | Metric::Ceil2d
+                | Metric::Att
+        );
+
+        let mut result = Self {
+            store,
+            nodes: (0..n_nodes).collect(),
+            axes: vec![0; n_nodes],
+            prune,
+        };
+
+        if store.dim() > 0 {
+            result.build(0, n_nodes);
+        }
+        result
+    }
+
+    /// Splits the range along the axis on which its coordinates spread the most.
+    fn build(&mut self, lo: usize, hi: usize) {
+        if hi - lo < 2 {
+            return;
+        }
+
+        let store = self.store;
+        let axis = (0..store.dim())
+            .map(|axis| {
+                let (min, max) = self.nodes[lo..hi].iter().fold(
+                    (Scalar::MAX, Scalar::MIN),
+                    |(min, max), node| {
+                        let x = store.coords(node)[axis];
+                        (min.min(x), max.max(x))
+                    },
+                );
+                (max - min, axis)
+            })
+            .fold((Scalar::MIN, 0), |a, b| if b.0 > a.0 { b } else { a })
+            .1;
+
+        let mid = lo + (hi - lo) / 2;
+        self.nodes[lo..hi].select_nth_unstable_by(mid - lo, |a, b| {
+            store.coords(a)[axis].total_cmp(&store.coords(b)[axis])
+        });
+        self.axes[mid] = axis;
+
+        self.build(lo, mid);
+        self.build(mid + 1, hi);
+    }
+
+    /// Returns the `k` nearest nodes of a node, sorted by distance and index.
+    fn nearest(&self, base: usize, k: usize) -> Vec<(TourNode, CandidateScore)> {
+        let mut list = Vec::with_capacity(k + 1);
+        self.search(base, k, 0, self.nodes.len(), &mut list);
+        list.into_iter()
+            .map(|(d, node)| (TourNode::new(node), CandidateScore::new(d, 0.)))
+            .collect()
+    }
+
+    fn search(&self, base: usize, k: usize, lo: usize, hi: usize, list: &mut Vec<(Scalar, usize)>) {
+        if lo >= hi {
+            return;
+        }
+
+        let mid = lo + (hi - lo) / 2;
+        let node = self.nodes[mid];
+        if node != base {
+            insert_nearest(list, k, (self.store.cost(&base, &node), node));
+        }
+
+        if !self.prune {
+            self.search(base, k, lo, mid, list);
+            self.search(base, k, mid + 1, hi, list);
+            return;
+        }
+
+        let axis = self.axes[mid];
+        let origin = self.store.coords(&base);
+        let split = self.store.coords(&node)[axis];
+        let (near, far) = if origin[axis] < split {
+            ((lo, mid), (mid + 1, hi))
+        } else {
+            ((mid + 1, hi), (lo, mid))
+        };
+
+        self.search(base, k, near.0, near.1, list);
+
+        // Nodes beyond the split are at least as far away as the projection of the base node
+        // onto the splitting plane.
+        let mut projected = [0.; 3];
+        projected[..origin.len()].copy_from_slice(origin);
+        projected[axis] = split;
+        let bound = self.store.metric().cost(origin, &projected[..origin.len()]);
+
+        if list.len() < k || list.last().is_some_and(|last| bound <= last.0) {
+            self.search(base, k, far.0, far.1, list);
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::{
-    alg::lkh::move_2_opt,
+    alg::{lkh::move_2_opt, CandidateSet},
     tour::{NodeRel, Tour, TourNode, UpdateTourError},
     Scalar,
 };
@@ -8,6 +8,7 @@ use super::types::SearchResult;
 
 pub fn search_2_opt<T>(
     tour: &mut T,
+    cands: &CandidateSet,
     base: &TourNode,
     base_s: &TourNode,
 ) -> Result<SearchResult, UpdateTourError>
@@ -18,7 +19,7 @@ where
     let mut _g2_best = Scalar::MIN;
     let pair = None;
 
-    for cand in base_s.candidates() {
+    for cand in cands.get(base_s) {
         let g1 = g0 - tour.distance(base_s, cand);
         if tour.relation(base_s, cand) != NodeRel::None || g1 <= 0. {
             continue;
@@ -56,6 +57,7 @@ where
     Err(UpdateTourError::SearchFailed)
 }
 
+/// Not implemented yet, and thus always returns [`UpdateTourError::UnsupportedMove`].
 pub fn search_3_opt<T>(
     _tour: &mut T,
     _head_1: &TourNode,
@@ -64,5 +66,5 @@ pub fn search_3_opt<T>(
 where
     T: Tour,
 {
-    todo!()
+    Err(UpdateTourError::UnsupportedMove)
 }
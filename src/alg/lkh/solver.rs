@@ -1,90 +1,129 @@
 use std::collections::VecDeque;
 
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
 use crate::{
-    alg::tour_gen::init_tour,
-    tour::{NodeStatus, Tour, TourNode, TourOrder, UpdateTourError},
+    alg::{tour_gen::init_tour, CandidateSet, NodeStatus, NodeTable},
+    tour::{Tour, TourNode, TourOrder, UpdateTourError},
     Scalar,
 };
 
 use super::{searches::search_2_opt, types::SearchResult, KOpt};
 
-pub fn solve_lkh<T>(tour: &mut T, kopt: KOpt, trials: usize) -> Result<(), UpdateTourError>
+/// Improves a tour with the Lin-Kernighan heuristics and returns the best tour order found.
+///
+/// The search only considers the nodes in `cands` when adding new edges. The first trial starts
+/// from `init` if such order is given, otherwise from the natural order of the tour's nodes.
+/// Every further trial starts from the best tour order found so far, perturbed by a random
+/// double-bridge move, which the 2-opt search cannot undo in a single step. The kicks are drawn
+/// from a fixed seed, thus repeated runs return the same tour. The best tour order found across
+/// all trials is applied to the tour before returning.
+///
+/// Returns [`UpdateTourError::UnsupportedMove`] without changing the tour if `kopt` is
+/// [`KOpt::Opt3`], which is not implemented yet.
+pub fn solve_lkh<T>(
+    tour: &mut T,
+    cands: &CandidateSet,
+    kopt: KOpt,
+    trials: usize,
+    init: Option<&TourOrder>,
+) -> Result<TourOrder, UpdateTourError>
 where
     T: Tour,
 {
+    if kopt != KOpt::Opt2 {
+        return Err(UpdateTourError::UnsupportedMove);
+    }
+
     let len = tour.len();
     let mut best_order = TourOrder::default();
-
-    for _ in 0..trials {
-        init_tour(tour)?;
-
-        let original_order = tour.tour_order();
-        let mut current_cost = original_order.cost();
-
-        let mut active = VecDeque::with_capacity(len);
-
-        for mut node in tour.itr() {
-            if node.is_best_neighbours(&tour.successor(&node).unwrap(), 0)
-                || node.is_best_neighbours(&tour.predecessor(&node).unwrap(), 0)
-            {
-                node.set_status(NodeStatus::Fixed);
-            } else {
-                node.set_status(NodeStatus::Active);
-                active.push_back(node)
+    let mut status = NodeTable::new(len, NodeStatus::Active);
+    let mut rng = StdRng::seed_from_u64(KICK_SEED);
+
+    for trial in 0..trials {
+        if trial == 0 {
+            match init {
+                Some(order) => tour.apply(order)?,
+                None => init_tour(tour)?,
             }
+        } else if len < 4 {
+            // A double bridge needs four non-empty paths, and smaller tours have a single cycle.
+            break;
+        } else {
+            tour.apply(&double_bridge(&best_order, &mut rng))?;
         }
 
-        while let Some(mut base) = active.pop_front() {
-            base.set_status(NodeStatus::Fixed);
+        let mut active: VecDeque<TourNode> = tour.itr().collect();
+        status.fill(NodeStatus::Active);
 
-            let successor = match tour.successor(&base) {
-                Some(s) => s,
-                None => return Err(UpdateTourError::NodeNotFound),
-            };
+        while let Some(base) = active.pop_front() {
+            status[&base] = NodeStatus::Fixed;
 
-            let predecessor = match tour.predecessor(&base) {
-                Some(p) => p,
-                None => return Err(UpdateTourError::NodeNotFound),
-            };
-
-            let mut flag = true;
+            // Searches in both directions by temporarily reversing the tour, so that the
+            // predecessor of the base node becomes its successor.
             for ii in 0..2 {
-                let gain = if ii == 1 {
-                    search(tour, kopt, &base, &successor)?
-                } else {
-                    match tour.relation(&base, &predecessor) {
-                        crate::tour::NodeRel::Predecessor => tour.rev(),
-                        crate::tour::NodeRel::Successor => {}
-                        crate::tour::NodeRel::None => return Err(UpdateTourError::BrokenTour),
-                    };
-
-                    search(tour, kopt, &base, &predecessor)?
+                if ii == 1 {
+                    tour.rev();
+                }
+
+                let successor = match tour.successor(&base) {
+                    Some(s) => s,
+                    None => return Err(UpdateTourError::NodeNotFound),
                 };
 
-                if gain > 0. {
-                    current_cost -= gain;
-                    break;
-                } else {
-                    base.set_status(NodeStatus::Active);
+                let gain = search(tour, cands, kopt, &base, &successor)?;
 
-                    if flag {
+                if gain > 0. {
+                    // The neighbourhood of the base node has changed, thus the node must be
+                    // examined again.
+                    if status[&base] != NodeStatus::Active {
+                        status[&base] = NodeStatus::Active;
                         active.push_back(base);
-                        flag = false;
                     }
+                    break;
                 }
             }
         }
 
-        if current_cost < best_order.cost() {
+        if tour.total_distance() < best_order.cost() {
             best_order = tour.tour_order();
         }
     }
 
-    Ok(())
+    if !best_order.is_empty() {
+        tour.apply(&best_order)?;
+    }
+
+    Ok(best_order)
+}
+
+/// The seed of the random double-bridge kicks between trials.
+const KICK_SEED: u64 = 0x6c6b68;
+
+/// Cuts the order into four non-empty paths A, B, C and D at random, and reconnects them as
+/// A C B D.
+fn double_bridge(order: &TourOrder, rng: &mut StdRng) -> TourOrder {
+    let v = order.order();
+    let len = v.len();
+    let p1 = rng.gen_range(1..len - 2);
+    let p2 = rng.gen_range(p1 + 1..len - 1);
+    let p3 = rng.gen_range(p2 + 1..len);
+
+    let mut result = TourOrder::with_capacity(len);
+    for &idx in v[..p1]
+        .iter()
+        .chain(&v[p2..p3])
+        .chain(&v[p1..p2])
+        .chain(&v[p3..])
+    {
+        result.add(idx);
+    }
+    result
 }
 
 fn search<T>(
     tour: &mut T,
+    cands: &CandidateSet,
     kopt: KOpt,
     base: &TourNode,
     base_s: &TourNode,
@@ -96,8 +135,12 @@ where
 
     while let Some(targ) = next {
         let result = match kopt {
-            KOpt::Opt2 => search_2_opt(tour, base, &targ)?,
-            KOpt::Opt3 => todo!(),
+            KOpt::Opt2 => match search_2_opt(tour, cands, base, &targ) {
+                Ok(result) => result,
+                Err(UpdateTourError::SearchFailed) => return Ok(0.),
+                Err(err) => return Err(err),
+            },
+            KOpt::Opt3 => return Err(UpdateTourError::UnsupportedMove),
         };
 
         match result {
@@ -1,10 +1,11 @@
 use crate::{tour::TourNode, Scalar};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum KOpt {
     /// Corresponds to the 2-opt case.
     Opt2,
-    /// Corresponds to the 3-opt case.
+    /// Corresponds to the 3-opt case, which the solver does not support yet.
     Opt3,
 }
 
@@ -1,15 +1,49 @@
 mod tour_gen;
+pub use tour_gen::repair_tour_order;
 
 pub mod lkh;
 
+mod candidates;
+pub use candidates::{CandidateScore, CandidateSet};
+
 mod cand_gen;
-pub use cand_gen::cand_gen_nn;
+pub use cand_gen::{cand_gen_nn, cand_gen_quadrant};
+
+mod cand_io;
+pub use cand_io::{read_candidates, read_pi, write_candidates, write_pi};
+
+mod kdtree;
+pub use kdtree::cand_gen_kd;
+
+mod delaunay;
+pub use delaunay::cand_gen_delaunay;
+
+mod popmusic;
+pub use popmusic::cand_gen_popmusic;
+
+mod alpha;
+pub use alpha::{cand_gen_alpha, lower_bound, pi_ascent};
+
+mod state;
+pub use state::NodeStatus;
+pub use state::NodeTable;
 
 pub mod solvers;
 
 mod tests;
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SolverKind {
+    /// Constructs a tour greedily, starting from each of the given nodes. Ignores the initial
+    /// order of a run configuration.
     Greedy(Vec<usize>),
+    /// Improves a tour with the Lin-Kernighan heuristics.
+    Lkh {
+        /// The k-opt move used in the search.
+        kopt: lkh::KOpt,
+        /// The number of trials. Each trial after the first restarts from the best tour found so
+        /// far, perturbed by a random double-bridge move.
+        trials: usize,
+    },
 }
@@ -0,0 +1,259 @@
+use std::collections::VecDeque;
+
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+use crate::{
+    tour::{Tour, TourNode},
+    Scalar,
+};
+
+use super::{CandidateScore, CandidateSet};
+
+/// The number of nodes in each subproblem.
+const SUBPATH_LEN: usize = 50;
+
+/// Generates candidates for each node from the edges of several tours which are obtained with the
+/// POPMUSIC matheuristic of Taillard and Helsgaun.
+///
+/// Each tour is first built from a random sample of the nodes, into which the remaining nodes are
+/// inserted next to their nearest sample node. The tour is then improved by optimising overlapping
+/// subpaths of up to 50 nodes with fixed end nodes, until no subpath can be improved any more. The
+/// subpaths are optimised with 2-opt moves and with 3-opt moves, which swap two adjacent segments
+/// or reverse them, and include the Or-opt moves. Each node receives up to `k` of its neighbours
+/// in the `n_tours` tours, sorted by distance, and nodes at equal distance by their indices.
+///
+/// The method only requires the distances between nodes and thus works with any metric. Building
+/// a tour takes O(N^1.5) time, and optimising it typically takes O(N) time. The tours are
+/// randomised with the given seed.
+pub fn cand_gen_popmusic<T>(tour: &T, k: usize, n_tours: usize, seed: u64) -> CandidateSet
+where
+    T: Tour,
+{
+    let n_nodes = tour.len();
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut neighbours = vec![Vec::with_capacity(2 * n_tours); n_nodes];
+
+    if n_nodes > 1 {
+        for _ in 0..n_tours {
+            let mut order = build_tour(tour, (0..n_nodes).collect(), &mut rng);
+            optimise_tour(tour, &mut order);
+
+            for (idx, &a) in order.iter().enumerate() {
+                let b = order[(idx + 1) % n_nodes];
+                if a != b && !neighbours[a].contains(&b) {
+                    neighbours[a].push(b);
+                    neighbours[b].push(a);
+                }
+            }
+        }
+    }
+
+    let mut result = CandidateSet::with_capacity(n_nodes, k);
+    for (base, list) in neighbours.iter_mut().enumerate() {
+        list.sort_by(|a, b| {
+            tour.distance_at(&base, a)
+                .total_cmp(&tour.distance_at(&base, b))
+                .then(a.cmp(b))
+        });
+        list.truncate(k);
+
+        result.push(list.iter().map(|&node| {
+            let score = CandidateScore::new(tour.distance_at(&base, &node), 0.);
+            (TourNode::new(node), score)
+        }));
+    }
+
+    result
+}
+
+/// Builds a tour by ordering a random sample of the nodes recursively and inserting each of the
+/// remaining nodes, again ordered recursively, after its nearest sample node.
+fn build_tour<T>(tour: &T, mut nodes: Vec<usize>, rng: &mut StdRng) -> Vec<usize>
+where
+    T: Tour,
+{
+    nodes.shuffle(rng);
+    if nodes.len() <= 8 {
+        return nodes;
+    }
+
+    let n_samples = (nodes.len() as Scalar).sqrt().ceil() as usize;
+    let rest = nodes.split_off(n_samples);
+    let samples = build_tour(tour, nodes, rng);
+
+    let mut groups = vec![Vec::new(); samples.len()];
+    for node in rest {
+        let nearest = (0..samples.len())
+            .map(|pos| (tour.distance_at(&node, &samples[pos]), pos))
+            .fold((Scalar::MAX, 0), |a, b| if b.0 < a.0 { b } else { a })
+            .1;
+        groups[nearest].push(node);
+    }
+
+    let mut result = Vec::with_capacity(samples.len() + groups.iter().map(Vec::len).sum::<usize>());
+    for (sample, group) in samples.into_iter().zip(groups) {
+        result.push(sample);
+        result.extend(build_tour(tour, group, rng));
+    }
+    result
+}
+
+/// Optimises the subpaths of a tour until none of them can be improved any more.
+///
+/// The subpaths start at regular intervals and overlap. Once a subpath is improved, the subpaths
+/// overlapping with it are queued for optimisation again.
+fn optimise_tour<T>(tour: &T, order: &mut [usize])
+where
+    T: Tour,
+{
+    let n_nodes = order.len();
+    if n_nodes < 4 {
+        return;
+    }
+
+    let len = SUBPATH_LEN.min(n_nodes);
+    // Small tours fit into a single subpath, which leaves out only the edge closing the tour.
+    // Shifting the subpath by one node ensures that each pair of edges is considered together.
+    let step = if len == n_nodes { 1 } else { len / 2 };
+    let n_paths = n_nodes.div_ceil(step);
+    let reach = ((len - 1) / step + 1).min(n_paths / 2);
+
+    let mut queue: VecDeque<_> = (0..n_paths).collect();
+    let mut queued = vec![true; n_paths];
+    let mut nodes = vec![0; len];
+    let mut path = vec![0; len];
+    let mut costs = vec![0.; len * len];
+
+    while let Some(idx) = queue.pop_front() {
+        queued[idx] = false;
+        let start = idx * step;
+        for (pos, node) in nodes.iter_mut().enumerate() {
+            *node = order[(start + pos) % n_nodes];
+        }
+        for (pos, cost) in costs.iter_mut().enumerate() {
+            *cost = tour.distance_at(&nodes[pos / len], &nodes[pos % len]);
+        }
+        path.iter_mut().enumerate().for_each(|(pos, p)| *p = pos);
+
+        if optimise_path(&costs, &mut path) {
+            for (pos, &p) in path.iter().enumerate() {
+                order[(start + pos) % n_nodes] = nodes[p];
+            }
+
+            for offset in 1..=reach {
+                for other in [(idx + offset) % n_paths, (idx + n_paths - offset) % n_paths] {
+                    if !queued[other] {
+                        queued[other] = true;
+                        queue.push_back(other);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Optimises a path with fixed end nodes by 2-opt and 3-opt moves, and returns ```true``` if the
+/// path has been improved.
+///
+/// The path consists of the positions of its nodes in the row-major cost matrix `costs`.
+pub(super) fn optimise_path(costs: &[Scalar], path: &mut [usize]) -> bool {
+    let len = path.len();
+    let d = |a: usize, b: usize| costs[a * len + b];
+    let mut improved = false;
+    let mut changed = true;
+
+    while changed {
+        changed = false;
+
+        // 2-opt: replaces the edges after positions ii and jj by reversing the path between them.
+        for ii in 0..len - 1 {
+            for jj in ii + 2..len - 1 {
+                let (a, b, c, e) = (path[ii], path[ii + 1], path[jj], path[jj + 1]);
+                if d(a, c) + d(b, e) < d(a, b) + d(c, e) - tolerance(d(a, b)) {
+                    path[ii + 1..=jj].reverse();
+                    changed = true;
+                }
+            }
+        }
+
+        // 3-opt: removes the edges after positions ii, jj and ll, and reconnects the segments
+        // s1 = (ii, jj] and s2 = (jj, ll] by swapping them, reversing one of them in the process,
+        // or by reversing both in place. Or-opt moves are the swaps with a short segment.
+        for ii in 0..len - 1 {
+            for jj in ii + 1..len - 1 {
+                for ll in jj + 1..len - 1 {
+                    if three_opt(&d, path, ii, jj, ll) {
+                        changed = true;
+                    }
+                }
+            }
+        }
+
+        improved |= changed;
+    }
+
+    improved
+}
+
+/// Applies the best reconnection of the segments (ii, jj] and (jj, ll] of a path if it shortens
+/// the path, and returns ```true``` if so.
+fn three_opt<D>(d: &D, path: &mut [usize], ii: usize, jj: usize, ll: usize) -> bool
+where
+    D: Fn(usize, usize) -> Scalar,
+{
+    let (a, b, c) = (path[ii], path[ii + 1], path[jj]);
+    let (e, f, g) = (path[jj + 1], path[ll], path[ll + 1]);
+    let removed = d(a, b) + d(c, e) + d(f, g);
+
+    // The reconnections a-s2-s1-g, a-s2-rev(s1)-g, a-rev(s2)-s1-g and a-rev(s1)-rev(s2)-g.
+    // Reversing both segments in place is the only one which is not a pure segment swap.
+    let added = [
+        d(a, e) + d(f, b) + d(c, g),
+        d(a, e) + d(f, c) + d(b, g),
+        d(a, f) + d(e, b) + d(c, g),
+        d(a, c) + d(b, f) + d(e, g),
+    ];
+    let (case, best) =
+        added.iter().enumerate().fold(
+            (0, Scalar::MAX),
+            |acc, (case, &x)| {
+                if x < acc.1 {
+                    (case, x)
+                } else {
+                    acc
+                }
+            },
+        );
+
+    // A NaN gain, e.g. from a node with NaN coordinates, is no improvement.
+    let gain = removed - best;
+    if gain.is_nan() || gain <= tolerance(removed) {
+        return false;
+    }
+
+    let (s1_len, s2_len) = (jj - ii, ll - jj);
+    let segs = &mut path[ii + 1..=ll];
+    match case {
+        0 => segs.rotate_left(s1_len),
+        1 => {
+            segs.rotate_left(s1_len);
+            segs[s2_len..].reverse();
+        }
+        2 => {
+            segs.rotate_left(s1_len);
+            segs[..s2_len].reverse();
+        }
+        _ => {
+            segs[..s1_len].reverse();
+            segs[s1_len..].reverse();
+        }
+    }
+    true
+}
+
+/// Returns the smallest gain which counts as an improvement, so that rounding errors cannot make
+/// the search cycle.
+#[inline]
+fn tolerance(value: Scalar) -> Scalar {
+    1e-9 * value.abs().max(1.)
+}
@@ -1,12 +1,17 @@
 use crate::{
-    data::GetIndex,
-    tour::{NodeStatus, Tour, TourOrder, UpdateTourError},
+    tour::{Tour, TourOrder, UpdateTourError},
     Scalar,
 };
 
+use super::{CandidateSet, NodeStatus, NodeTable};
+
 /// Uses greedy algorithm to construct a tour.
+///
+/// From each node, the walk continues with the first unvisited candidate in `cands`, or with
+/// the nearest unvisited node if all candidates have been visited.
 pub fn solve_greedy<T>(
     tour: &mut T,
+    cands: &CandidateSet,
     starters: &[usize],
 ) -> Result<Option<TourOrder>, UpdateTourError>
 where
@@ -19,9 +24,10 @@ where
     let len = tour.len();
     let mut best_tour = None;
     let mut best_cost = Scalar::MAX;
+    let mut status = NodeTable::new(len, NodeStatus::Active);
 
     for starter in starters {
-        tour.reset();
+        status.fill(NodeStatus::Active);
 
         let mut v = Vec::with_capacity(tour.len());
         let mut node = match tour.get(*starter) {
@@ -29,13 +35,13 @@ where
             None => return Err(UpdateTourError::NodeNotFound),
         };
 
-        v.push(node.index().get());
-        node.set_status(NodeStatus::Fixed);
+        v.push(node.index());
+        status[&node] = NodeStatus::Fixed;
 
         while v.len() != len {
             let mut chosen = None;
-            for cand in node.candidates() {
-                if !cand.is_status(NodeStatus::Active) {
+            for cand in cands.get(&node) {
+                if status[cand] != NodeStatus::Active {
                     continue;
                 }
 
@@ -43,12 +49,12 @@ where
                 break;
             }
 
-            let mut next = chosen.unwrap_or_else(|| {
+            let next = chosen.unwrap_or_else(|| {
                 let mut d = Scalar::MAX;
                 let mut cand = None;
 
                 for next_node in tour.itr() {
-                    if !next_node.is_status(NodeStatus::Active) {
+                    if status[&next_node] != NodeStatus::Active {
                         continue;
                     }
 
@@ -65,8 +71,8 @@ where
                 }
             });
 
-            next.set_status(NodeStatus::Fixed);
-            v.push(next.index().get());
+            status[&next] = NodeStatus::Fixed;
+            v.push(next.index());
             node = next;
         }
 
@@ -0,0 +1,87 @@
+use std::ops::{Index, IndexMut};
+
+use crate::tour::TourNode;
+
+/// The status of a node during the run of an algorithm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum NodeStatus {
+    Active,
+    Fixed,
+    Undefined,
+}
+
+/// A side table which stores a value for every node of a tour, keyed by the node's index.
+///
+/// Algorithms keep their per-node state in such tables instead of in the tour, so that the same
+/// tour can be shared by several algorithms and their states can be reused or dropped
+/// independently of it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct NodeTable<T> {
+    values: Vec<T>,
+}
+
+impl<T> NodeTable<T>
+where
+    T: Clone,
+{
+    /// Creates a table for `len` nodes, each of which holds `value`.
+    pub fn new(len: usize, value: T) -> Self {
+        Self {
+            values: vec![value; len],
+        }
+    }
+
+    /// Sets the values of all nodes.
+    pub fn fill(&mut self, value: T) {
+        for v in &mut self.values {
+            *v = value.clone();
+        }
+    }
+}
+
+impl<T> NodeTable<T> {
+    /// Returns the number of nodes in the table.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Returns ```true``` if the table has no nodes.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the value of a node, or ```None``` if the node is not in the table.
+    #[inline]
+    pub fn get(&self, node: &TourNode) -> Option<&T> {
+        self.values.get(node.index())
+    }
+
+    /// Returns the mutable value of a node, or ```None``` if the node is not in the table.
+    #[inline]
+    pub fn get_mut(&mut self, node: &TourNode) -> Option<&mut T> {
+        self.values.get_mut(node.index())
+    }
+
+    /// Returns the iterator over the values of all nodes, in the order of their indices.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.values.iter()
+    }
+}
+
+impl<T> Index<&TourNode> for NodeTable<T> {
+    type Output = T;
+
+    #[inline]
+    fn index(&self, node: &TourNode) -> &Self::Output {
+        &self.values[node.index()]
+    }
+}
+
+impl<T> IndexMut<&TourNode> for NodeTable<T> {
+    #[inline]
+    fn index_mut(&mut self, node: &TourNode) -> &mut Self::Output {
+        &mut self.values[node.index()]
+    }
+}
@@ -1,14 +1,22 @@
 #![cfg(test)]
 use crate::{
     combine_range,
+    data::{DataStore, Metric, NodeKind},
+    input::{InputError, RecordErrorKind},
     tour::{
         tests::{create_store, test_tour_order},
-        Tour, TourOrder, TwoLevelList,
+        Tour, TourNode, TourOrder, TwoLevelList,
     },
-    tour_order,
+    tour_order, Scalar,
 };
 
-use super::lkh::{move_2_opt, move_3_opt, move_4_opt, Opt3Move, Opt4SeqMove};
+use super::{
+    cand_gen_alpha, cand_gen_delaunay, cand_gen_kd, cand_gen_nn, cand_gen_popmusic,
+    cand_gen_quadrant,
+    lkh::{move_2_opt, move_3_opt, move_4_opt, solve_lkh, KOpt, Opt3Move, Opt4SeqMove},
+    lower_bound, pi_ascent, read_candidates, read_pi, repair_tour_order, write_candidates,
+    write_pi, CandidateScore, CandidateSet, NodeStatus, NodeTable,
+};
 
 #[test]
 fn test_move_2_opt() {
@@ -102,6 +110,9 @@ fn test_move_4_opt() {
             mv,
         );
         test_tour_order(&tour, &exp);
+
+        // The total distance follows each of the flips the move consists of.
+        assert!((tour.measure(&exp) - tour.total_distance()).abs() < 1e-9);
     };
 
     fn4(
@@ -202,35 +213,613 @@ fn test_move_4_opt() {
     );
 }
 
-// fn create_repo_2() -> Repo {
-//     let mut repo = RepoBuilder::new(tspf::WeightKind::Euc2d)
-//         .capacity(16)
-//         .build();
-//     repo.add(NodeKind::Customer, vec![38.24, 20.42, 0.]);
-//     repo.add(NodeKind::Customer, vec![39.57, 26.15, 0.]);
-//     repo.add(NodeKind::Customer, vec![40.56, 25.32, 0.]);
-//     repo.add(NodeKind::Customer, vec![36.26, 23.12, 0.]);
-//     repo.add(NodeKind::Customer, vec![33.48, 10.54, 0.]);
-//     repo.add(NodeKind::Customer, vec![37.56, 12.19, 0.]);
-//     repo.add(NodeKind::Customer, vec![38.42, 13.11, 0.]);
-//     repo.add(NodeKind::Customer, vec![37.52, 20.44, 0.]);
-//     repo.add(NodeKind::Customer, vec![41.23, 9.10, 0.]);
-//     repo.add(NodeKind::Customer, vec![41.17, 13.05, 0.]);
-//     repo.add(NodeKind::Customer, vec![36.08, -5.21, 0.]);
-//     repo.add(NodeKind::Customer, vec![38.47, 15.13, 0.]);
-//     repo.add(NodeKind::Customer, vec![38.15, 15.35, 0.]);
-//     repo.add(NodeKind::Customer, vec![37.51, 15.17, 0.]);
-//     repo.add(NodeKind::Customer, vec![35.49, 14.32, 0.]);
-//     repo.add(NodeKind::Customer, vec![39.36, 19.56, 0.]);
-//     repo
-// }
-
-// #[test]
-// fn test_cand_gen_nn() {
-//     let mut tour = TwoLevelList::new(create_repo_2(), 4);
-//     cand_gen_nn(&mut tour, 5);
-
-//     for node in tour.itr() {
-//         assert_eq!(5, node.candidates().len());
-//     }
-// }
+#[test]
+fn test_repair_tour_order() {
+    let tour = TwoLevelList::new(&create_store(10), 4);
+
+    // Duplicated and unknown nodes are dropped, missing nodes are inserted cheaply.
+    let repaired = repair_tour_order(&tour, &[0, 1, 2, 2, 42, 6, 7, 8, 9]);
+    assert_eq!(&vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9], repaired.order());
+    assert_eq!(tour.measure(&repaired), repaired.cost());
+
+    let repaired = repair_tour_order(&tour, &[]);
+    assert_eq!(10, repaired.len());
+
+    let repaired = repair_tour_order(&tour, &[9, 8, 7]);
+    let mut sorted = repaired.order().clone();
+    sorted.sort_unstable();
+    assert_eq!((0..10).collect::<Vec<_>>(), sorted);
+}
+
+#[test]
+fn test_solve_lkh_warm_start() {
+    let mut store = DataStore::<()>::with_capacity(Metric::Euc2d, 30);
+    for ii in 0..30 {
+        let (x, y) = ((ii * 11 % 17) as Scalar, (ii * 7 % 23) as Scalar);
+        store.add(NodeKind::Target, vec![x, y], ());
+    }
+    store.compute();
+
+    let mut tour = TwoLevelList::new(&store, 6);
+    let cands = cand_gen_nn(&tour, 8);
+
+    let cold = solve_lkh(&mut tour, &cands, KOpt::Opt2, 1, None).unwrap();
+    assert_eq!(30, cold.len());
+    assert!(cold.cost() < tour.measure(&TourOrder::with_nat_ord(30)));
+
+    // Starting from a locally optimal tour cannot make it any worse.
+    let warm = solve_lkh(&mut tour, &cands, KOpt::Opt2, 1, Some(&cold)).unwrap();
+    assert!(warm.cost() <= cold.cost());
+    test_tour_order(&tour, &warm);
+}
+
+#[test]
+fn test_solve_lkh_trials() {
+    let mut tour = TwoLevelList::new(&create_scattered_store(100), 10);
+    let cands = cand_gen_nn(&tour, 5);
+
+    let single = solve_lkh(&mut tour, &cands, KOpt::Opt2, 1, None).unwrap();
+    let multi = solve_lkh(&mut tour, &cands, KOpt::Opt2, 10, None).unwrap();
+    test_tour_order(&tour, &multi);
+
+    // Later trials start from perturbed copies of the best tour instead of repeating the first.
+    assert!(multi.cost() < single.cost());
+
+    let again = solve_lkh(&mut tour, &cands, KOpt::Opt2, 10, None).unwrap();
+    assert_eq!(multi.order(), again.order());
+}
+
+#[test]
+fn test_candidates() {
+    let tour = TwoLevelList::new(&create_store(4), 4);
+    let node = |idx: usize| tour.get(idx).unwrap();
+
+    let score = |alpha: Scalar| CandidateScore::new(1., alpha);
+
+    let mut cands = CandidateSet::new();
+    assert!(cands.is_empty());
+
+    cands.push(vec![(node(1), score(0.)), (node(2), score(1.))]);
+    cands.push(vec![]);
+    cands.push(vec![(node(0), score(1.))]);
+    assert_eq!(3, cands.len());
+    assert_eq!(3, cands.n_candidates());
+    assert_eq!(&[node(1), node(2)], cands.get(&node(0)));
+    assert_eq!(&[score(0.), score(1.)], cands.scores(&node(0)));
+    assert!(cands.get(&node(1)).is_empty());
+    assert_eq!(&[node(0)], cands.get(&node(2)));
+    assert!(cands.contains(&node(2), &node(0)));
+    assert!(!cands.contains(&node(0), &node(3)));
+
+    // Nodes without a list have no candidates.
+    assert!(cands.get(&node(3)).is_empty());
+    assert!(cands.scores(&node(3)).is_empty());
+
+    let lists: CandidateSet = (0..3)
+        .map(|idx| {
+            let zipped = cands.get(&node(idx)).iter().zip(cands.scores(&node(idx)));
+            zipped.map(|(&n, &s)| (n, s)).collect()
+        })
+        .collect();
+    assert_eq!(cands, lists);
+}
+
+#[test]
+fn test_candidate_file() {
+    let tour = TwoLevelList::new(&create_scattered_store(30), 5);
+    let pi = pi_ascent(&tour, 50);
+    let cands = cand_gen_alpha(&tour, 5, Some(&pi));
+
+    let mut file = Vec::new();
+    write_candidates(&mut file, &cands, 100.).unwrap();
+    let text = String::from_utf8(file.clone()).unwrap();
+    assert!(text.starts_with("30\n1 0 5 "));
+    assert!(text.ends_with("\n-1\nEOF\n"));
+
+    let result = read_candidates(&tour, file.as_slice(), 100.).unwrap();
+    assert_eq!(cands.len(), result.len());
+    for node in tour.itr() {
+        assert_eq!(cands.get(&node), result.get(&node));
+        for (expected, score) in cands.scores(&node).iter().zip(result.scores(&node)) {
+            assert_eq!(expected.distance, score.distance);
+            assert!((expected.alpha - score.alpha).abs() <= 0.005);
+        }
+    }
+
+    // Lines may come in any order, and the file may end without a terminator.
+    let text = "3\n\n2 0 1 3 0\n1 0 2 2 0 3 150\n3 1 0\n";
+    let tour = TwoLevelList::new(&create_store(3), 3);
+    let result = read_candidates(&tour, text.as_bytes(), 100.).unwrap();
+    let expected: Vec<_> = [2, 3].iter().map(|&id| tour.get(id - 1).unwrap()).collect();
+    assert_eq!(expected.as_slice(), result.get(&tour.get(0).unwrap()));
+    assert_eq!(1.5, result.scores(&tour.get(0).unwrap())[1].alpha);
+    assert!(result.get(&tour.get(2).unwrap()).is_empty());
+
+    match read_candidates(&tour, "4\n".as_bytes(), 100.) {
+        Err(InputError::NodesMismatched { expected, received }) => {
+            assert_eq!((3, 4), (expected, received))
+        }
+        _ => panic!("Dimension mismatch not reported."),
+    }
+
+    match read_candidates(&tour, "3\n1 0 0\n2 0 0\n-1\n".as_bytes(), 100.) {
+        Err(InputError::NodesMismatched { expected, received }) => {
+            assert_eq!((3, 2), (expected, received))
+        }
+        _ => panic!("Missing node not reported."),
+    }
+
+    let text = "3\n1 0 1 4 0\n2 0 2 1 0\n2 0 0\n3 0 1 1 x\n";
+    match read_candidates(&tour, text.as_bytes(), 100.) {
+        Err(InputError::InvalidRecords(errors)) => {
+            let errors: Vec<_> = errors
+                .iter()
+                .map(|e| (e.line(), e.kind().clone()))
+                .collect();
+            assert_eq!(
+                vec![
+                    (2, RecordErrorKind::InvalidNodeId("4".to_string())),
+                    (3, RecordErrorKind::MissingField { column: 5 }),
+                    (
+                        5,
+                        RecordErrorKind::InvalidNumber {
+                            column: 4,
+                            value: "x".to_string()
+                        }
+                    ),
+                ],
+                errors
+            );
+        }
+        _ => panic!("Invalid records not reported."),
+    }
+
+    let text = "3\n1 0 18446744073709551615 2 0\n2 0 1 2 0\n3 0 2 1 0 1 0\n";
+    match read_candidates(&tour, text.as_bytes(), 100.) {
+        Err(InputError::InvalidRecords(errors)) => {
+            let errors: Vec<_> = errors
+                .iter()
+                .map(|e| (e.line(), e.kind().clone()))
+                .collect();
+            assert_eq!(
+                vec![
+                    (
+                        2,
+                        RecordErrorKind::InvalidCount {
+                            column: 2,
+                            value: "18446744073709551615".to_string()
+                        }
+                    ),
+                    (3, RecordErrorKind::SelfCandidate("2".to_string())),
+                    (4, RecordErrorKind::DuplicateId("1".to_string())),
+                ],
+                errors
+            );
+        }
+        _ => panic!("Invalid candidates not reported."),
+    }
+
+    let err = write_candidates(Vec::new(), &cands, 0.).unwrap_err();
+    assert_eq!(
+        "precision must be positive and finite, got 0",
+        err.to_string()
+    );
+}
+
+#[test]
+fn test_pi_file() {
+    let tour = TwoLevelList::new(&create_scattered_store(30), 5);
+    let pi = pi_ascent(&tour, 50);
+
+    let mut file = Vec::new();
+    write_pi(&mut file, &pi, 100.).unwrap();
+    let result = read_pi(file.as_slice(), 30, 100.).unwrap();
+    assert_eq!(pi.len(), result.len());
+    for (expected, value) in pi.iter().zip(result.iter()) {
+        assert!((expected - value).abs() <= 0.005);
+    }
+
+    let result = read_pi("2\n2 -50\n1 25\nEOF\n".as_bytes(), 2, 100.).unwrap();
+    assert_eq!(vec![0.25, -0.5], result.iter().copied().collect::<Vec<_>>());
+
+    for &precision in &[0., -100., Scalar::NAN, Scalar::INFINITY] {
+        assert!(matches!(
+            read_pi(file.as_slice(), 30, precision),
+            Err(InputError::InvalidPrecision(_))
+        ));
+        assert!(matches!(
+            read_candidates(&tour, "30\n".as_bytes(), precision),
+            Err(InputError::InvalidPrecision(_))
+        ));
+        assert_eq!(
+            std::io::ErrorKind::InvalidInput,
+            write_pi(Vec::new(), &pi, precision).unwrap_err().kind()
+        );
+    }
+
+    match read_pi("2\n1 0\n1 5\n".as_bytes(), 2, 100.) {
+        Err(InputError::InvalidRecords(errors)) => {
+            assert_eq!(3, errors[0].line());
+            assert_eq!(
+                &RecordErrorKind::DuplicateId("1".to_string()),
+                errors[0].kind()
+            );
+        }
+        _ => panic!("Duplicate node not reported."),
+    }
+}
+
+#[test]
+fn test_candidate_set_operations() {
+    let tour = TwoLevelList::new(&create_store(6), 6);
+    let node = |idx: usize| tour.get(idx).unwrap();
+    let idx = |list: &[TourNode]| list.iter().map(|n| n.index()).collect::<Vec<_>>();
+
+    let nearest = cand_gen_nn(&tour, 2);
+    let alpha = cand_gen_alpha(&tour, 3, None);
+
+    let union = nearest.union(&alpha);
+    let intersection = nearest.intersect(&alpha);
+    for ii in 0..6 {
+        let (a, b) = (nearest.get(&node(ii)), alpha.get(&node(ii)));
+        let list = union.get(&node(ii));
+        assert_eq!(a, &list[..a.len()]);
+        assert!(b.iter().all(|cand| list.contains(cand)));
+        assert!(intersection
+            .get(&node(ii))
+            .iter()
+            .all(|cand| a.contains(cand) && b.contains(cand)));
+    }
+
+    // Node 0 only has a list with node 5, which is symmetrised into a list for node 5.
+    let dist = tour.distance_at(&0, &5);
+    let mut cands: CandidateSet = vec![vec![(node(5), CandidateScore::new(dist, 2.))]]
+        .into_iter()
+        .collect();
+    cands.make_symmetric();
+    assert_eq!(6, cands.len());
+    assert_eq!(vec![0], idx(cands.get(&node(5))));
+    assert_eq!(cands.scores(&node(0)), cands.scores(&node(5)));
+
+    // Tour edges are added with an alpha value of zero and thus ranked first.
+    cands.add_tour(&tour, &TourOrder::with_nat_ord(6));
+    assert_eq!(vec![5, 1], idx(cands.get(&node(0))));
+    cands.sort();
+    assert_eq!(vec![1, 5], idx(cands.get(&node(0))));
+    assert_eq!(vec![4, 0], idx(cands.get(&node(5))));
+    assert!(tour.itr().all(|n| cands.get(&n).len() == 2));
+
+    cands.truncate(1);
+    assert_eq!(6, cands.n_candidates());
+    assert_eq!(vec![1], idx(cands.get(&node(0))));
+
+    // Scores with a positive NaN are ranked after all others instead of causing a panic.
+    let mut cands: CandidateSet = vec![vec![
+        (node(3), CandidateScore::new(1., Scalar::NAN)),
+        (node(2), CandidateScore::new(Scalar::NAN, 0.)),
+        (node(1), CandidateScore::new(2., 0.)),
+    ]]
+    .into_iter()
+    .collect();
+    cands.sort();
+    assert_eq!(vec![1, 2, 3], idx(cands.get(&node(0))));
+}
+
+#[test]
+fn test_cand_gen_nn() {
+    let tour = TwoLevelList::new(&create_store(20), 4);
+    let cands = cand_gen_nn(&tour, 5);
+
+    assert_eq!(20, cands.len());
+    for node in tour.itr() {
+        let list = cands.get(&node);
+        assert_eq!(5, list.len());
+        assert!(!list.contains(&node));
+        for pair in list.windows(2) {
+            assert!(tour.distance(&node, &pair[0]) <= tour.distance(&node, &pair[1]));
+        }
+    }
+}
+
+fn create_scattered_store(n_nodes: usize) -> DataStore<()> {
+    let mut store = DataStore::<()>::with_capacity(Metric::Euc2d, n_nodes);
+    for ii in 0..n_nodes {
+        let (x, y) = ((ii * 37) % 101, (ii * 59) % 97);
+        store.add(NodeKind::Target, vec![x as Scalar, y as Scalar], ());
+    }
+    store.compute();
+    store
+}
+
+#[test]
+fn test_cand_gen_alpha() {
+    let tour = TwoLevelList::new(&create_scattered_store(30), 5);
+    let nearest = cand_gen_nn(&tour, 1);
+
+    for pi in [None, Some(pi_ascent(&tour, 50))].iter() {
+        let cands = cand_gen_alpha(&tour, 5, pi.as_ref());
+        assert_eq!(30, cands.len());
+        for node in tour.itr() {
+            let list = cands.get(&node);
+            assert_eq!(5, list.len());
+            assert!(!list.contains(&node));
+        }
+    }
+
+    // Without penalties, the edge to the nearest neighbour belongs to the minimum 1-tree and is
+    // the shortest edge with an alpha value of zero.
+    let cands = cand_gen_alpha(&tour, 5, None);
+    for node in tour.itr() {
+        let (first, nn) = (cands.get(&node)[0], nearest.get(&node)[0]);
+        assert_eq!(tour.distance(&node, &nn), tour.distance(&node, &first));
+    }
+
+    let cands = cand_gen_alpha(&tour, 29, None);
+    for node in tour.itr() {
+        let mut list: Vec<_> = cands.get(&node).iter().map(|n| n.index()).collect();
+        list.sort_unstable();
+        let expected: Vec<_> = (0..30).filter(|&ii| ii != node.index()).collect();
+        assert_eq!(expected, list);
+    }
+}
+
+#[test]
+fn test_lower_bound() {
+    let mut tour = TwoLevelList::new(&create_scattered_store(30), 5);
+    let pi = pi_ascent(&tour, 100);
+    let bound = lower_bound(&tour, None);
+    let improved = lower_bound(&tour, Some(&pi));
+    assert!(bound <= improved);
+
+    let cands = cand_gen_alpha(&tour, 5, Some(&pi));
+    let best = solve_lkh(&mut tour, &cands, KOpt::Opt2, 5, None).unwrap();
+    assert!(improved <= best.cost() + 1e-6);
+}
+
+#[test]
+#[should_panic(expected = "Expected a penalty for each of the 30 nodes, received 20.")]
+fn test_cand_gen_alpha_mismatched_pi() {
+    let tour = TwoLevelList::new(&create_scattered_store(30), 5);
+    cand_gen_alpha(&tour, 5, Some(&NodeTable::new(20, 0.)));
+}
+
+#[test]
+#[should_panic(expected = "Expected a penalty for each of the 30 nodes, received 40.")]
+fn test_lower_bound_mismatched_pi() {
+    let tour = TwoLevelList::new(&create_scattered_store(30), 5);
+    lower_bound(&tour, Some(&NodeTable::new(40, 0.)));
+}
+
+#[test]
+fn test_cand_gen_kd() {
+    // Both stores contain many nodes at equal distances, which are ordered by their indices.
+    let store = create_scattered_store(60);
+    let tour = TwoLevelList::new(&store, 8);
+    for &k in &[1, 5, 8] {
+        let expected = cand_gen_nn(&tour, k);
+        assert_eq!(expected, cand_gen_kd(&store, k, 1));
+        assert_eq!(expected, cand_gen_kd(&store, k, 4));
+    }
+
+    let store = create_store(10);
+    let tour = TwoLevelList::new(&store, 4);
+    assert_eq!(cand_gen_nn(&tour, 3), cand_gen_kd(&store, 3, 3));
+
+    // Lists are cut short if there are fewer nodes than candidates.
+    let cands = cand_gen_kd(&store, 12, 2);
+    assert_eq!(cand_gen_nn(&tour, 12), cands);
+    assert!(cands.iter().all(|list| list.len() == 9));
+}
+
+#[test]
+fn test_cand_gen_popmusic() {
+    // On a circle, every locally optimal tour follows the circle.
+    let mut store = DataStore::<()>::with_capacity(Metric::Euc2d, 12);
+    for ii in 0..12 {
+        let angle = ii as Scalar * std::f64::consts::PI / 6.;
+        store.add(NodeKind::Target, vec![angle.cos(), angle.sin()], ());
+    }
+    store.compute();
+    let tour = TwoLevelList::new(&store, 4);
+
+    let cands = cand_gen_popmusic(&tour, 5, 3, 0);
+    for node in tour.itr() {
+        let mut list: Vec<_> = cands.get(&node).iter().map(|n| n.index()).collect();
+        list.sort_unstable();
+        let mut expected = vec![(node.index() + 1) % 12, (node.index() + 11) % 12];
+        expected.sort_unstable();
+        assert_eq!(expected, list);
+    }
+
+    let store = create_scattered_store(300);
+    let tour = TwoLevelList::new(&store, 20);
+    let cands = cand_gen_popmusic(&tour, 6, 5, 1);
+    assert_eq!(cands, cand_gen_popmusic(&tour, 6, 5, 1));
+    for node in tour.itr() {
+        let list = cands.get(&node);
+        assert!(list.len() >= 2 && list.len() <= 6);
+        assert!(!list.contains(&node));
+        for pair in list.windows(2) {
+            assert!(tour.distance(&node, &pair[0]) <= tour.distance(&node, &pair[1]));
+        }
+    }
+}
+
+#[test]
+fn test_popmusic_optimise_path() {
+    use super::popmusic::optimise_path;
+
+    // Some of the paths have local optima under 2-opt and Or-opt which swapping longer segments
+    // still improves.
+    for n in (10..=30).step_by(4) {
+        let store = create_scattered_store(n);
+        let len = store.len();
+        let costs: Vec<_> = (0..len * len)
+            .map(|pos| store.cost(&(pos / len), &(pos % len)))
+            .collect();
+        let d = |a: usize, b: usize| costs[a * len + b];
+        let cost = |path: &[usize]| path.windows(2).map(|p| d(p[0], p[1])).sum::<Scalar>();
+
+        let mut path: Vec<_> = (0..len).collect();
+        let initial = cost(&path);
+        assert!(optimise_path(&costs, &mut path));
+        assert!(cost(&path) < initial);
+        assert_eq!((0, len - 1), (path[0], path[len - 1]));
+
+        // No reconnection of two adjacent segments shortens the optimised path any further.
+        let result = cost(&path);
+        for ii in 0..len - 1 {
+            for jj in ii + 1..len - 1 {
+                for ll in jj + 1..len - 1 {
+                    let (s1, s2) = (&path[ii + 1..=jj], &path[jj + 1..=ll]);
+                    let rev = |s: &[usize]| s.iter().rev().copied().collect::<Vec<_>>();
+                    for (first, second) in [
+                        (s2.to_vec(), s1.to_vec()),
+                        (s2.to_vec(), rev(s1)),
+                        (rev(s2), s1.to_vec()),
+                        (rev(s1), rev(s2)),
+                        (rev(&path[ii + 1..=ll]), Vec::new()),
+                    ] {
+                        let mut other = path[..=ii].to_vec();
+                        other.extend(first);
+                        other.extend(second);
+                        other.extend_from_slice(&path[ll + 1..]);
+                        assert!(cost(&other) >= result - 1e-6);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_cand_gen_quadrant() {
+    // Two clusters of nodes, the right one starting with its leftmost node.
+    let mut store = DataStore::<()>::with_capacity(Metric::Euc2d, 20);
+    for ii in 0..20 {
+        let offset = if ii < 10 { 100. } else { 0. };
+        let (x, y) = ((ii % 10) as Scalar, ((ii * 7) % 10) as Scalar);
+        store.add(NodeKind::Target, vec![offset + x, y], ());
+    }
+    store.compute();
+
+    let tour = TwoLevelList::new(&store, 5);
+    let border = tour.get(0).unwrap();
+    let is_left = |node: &TourNode| node.index() >= 10;
+
+    let nearest = cand_gen_nn(&tour, 8);
+    assert!(!nearest.get(&border).iter().any(is_left));
+
+    let cands = cand_gen_quadrant(&tour, 8);
+    assert_eq!(20, cands.len());
+    let list = cands.get(&border);
+    assert_eq!(8, list.len());
+    assert!(!list.contains(&border));
+    assert!(list.iter().any(is_left));
+    for pair in list.windows(2) {
+        assert!(tour.distance(&border, &pair[0]) <= tour.distance(&border, &pair[1]));
+    }
+
+    // Fewer slots than quadrants still reach across the border, since the nearest node of each
+    // quadrant comes before the second nearest of any.
+    let few = cand_gen_quadrant(&tour, 2);
+    let list = few.get(&border);
+    assert_eq!(2, list.len());
+    assert!(list.iter().any(is_left));
+    assert_eq!(nearest.get(&border)[0], list[0]);
+
+    let merged = nearest.union(&cands);
+    assert_eq!(nearest.get(&border), &merged.get(&border)[..8]);
+    for node in tour.itr() {
+        let list = merged.get(&node);
+        assert!(cands.get(&node).iter().all(|cand| list.contains(cand)));
+        for (ii, cand) in list.iter().enumerate() {
+            assert!(!list[ii + 1..].contains(cand));
+        }
+    }
+}
+
+#[test]
+fn test_cand_gen_delaunay() {
+    // The corners of a square around its centre.
+    let mut store = DataStore::<()>::with_capacity(Metric::Euc2d, 5);
+    for &(x, y) in &[(0., 0.), (4., 0.), (4., 4.), (0., 4.), (2., 2.)] {
+        store.add(NodeKind::Target, vec![x, y], ());
+    }
+    store.compute();
+    let tour = TwoLevelList::new(&store, 5);
+    let idx = |list: &[TourNode]| list.iter().map(|n| n.index()).collect::<Vec<_>>();
+
+    let cands = cand_gen_delaunay(&store, None, false);
+    assert_eq!(vec![4, 1, 3], idx(cands.get(&tour.get(0).unwrap())));
+    assert_eq!(vec![0, 1, 2, 3], idx(cands.get(&tour.get(4).unwrap())));
+
+    let cands = cand_gen_delaunay(&store, None, true);
+    assert_eq!(vec![4, 1, 3, 2], idx(cands.get(&tour.get(0).unwrap())));
+
+    let cands = cand_gen_delaunay(&store, Some(2), true);
+    assert!(cands.iter().all(|list| list.len() == 2));
+
+    // Collinear nodes, with one of them given twice.
+    let mut store = DataStore::<()>::with_capacity(Metric::Euc2d, 5);
+    for &x in &[3., 0., 1., 2., 1.] {
+        store.add(NodeKind::Target, vec![x, x], ());
+    }
+    store.compute();
+    let tour = TwoLevelList::new(&store, 5);
+
+    let cands = cand_gen_delaunay(&store, None, false);
+    assert_eq!(vec![3], idx(cands.get(&tour.get(0).unwrap())));
+    assert_eq!(vec![4, 1, 3], idx(cands.get(&tour.get(2).unwrap())));
+    assert_eq!(vec![2, 1, 3], idx(cands.get(&tour.get(4).unwrap())));
+}
+
+/// Returns a store of scattered nodes, one of which has a NaN coordinate.
+fn create_nan_store() -> DataStore<()> {
+    let mut store = DataStore::<()>::with_capacity(Metric::Euc2d, 20);
+    for ii in 0..20 {
+        let x = if ii == 7 {
+            Scalar::NAN
+        } else {
+            (ii * 37 % 101) as Scalar
+        };
+        store.add(NodeKind::Target, vec![x, (ii * 59 % 97) as Scalar], ());
+    }
+    store.compute();
+    store
+}
+
+#[test]
+fn test_cand_gen_non_finite() {
+    // Non-finite distances and coordinates are ordered instead of causing a panic.
+    let store = create_nan_store();
+    let tour = TwoLevelList::new(&store, 5);
+    assert_eq!(20, cand_gen_quadrant(&tour, 5).len());
+    assert_eq!(20, cand_gen_kd(&store, 5, 1).len());
+    assert_eq!(20, cand_gen_popmusic(&tour, 5, 3, 1).len());
+}
+
+#[test]
+#[should_panic(expected = "Delaunay candidates require finite coordinates.")]
+fn test_cand_gen_delaunay_non_finite() {
+    cand_gen_delaunay(&create_nan_store(), None, false);
+}
+
+#[test]
+fn test_node_table() {
+    let tour = TwoLevelList::new(&create_store(5), 5);
+    let (first, last) = (tour.get(0).unwrap(), tour.get(4).unwrap());
+
+    let mut status = NodeTable::new(tour.len(), NodeStatus::Active);
+    status[&last] = NodeStatus::Fixed;
+    assert_eq!(NodeStatus::Active, status[&first]);
+    assert_eq!(Some(&NodeStatus::Fixed), status.get(&last));
+    assert_eq!(
+        1,
+        status.iter().filter(|&&s| s == NodeStatus::Fixed).count()
+    );
+
+    status.fill(NodeStatus::Undefined);
+    assert!(status.iter().all(|&s| s == NodeStatus::Undefined));
+    assert_eq!(None, NodeTable::new(2, 0).get(&last));
+}
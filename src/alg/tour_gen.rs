@@ -1,7 +1,7 @@
 use crate::{
     combine_range,
     tour::{Tour, TourOrder, UpdateTourError},
-    tour_order,
+    tour_order, Scalar,
 };
 
 pub fn init_tour<T>(tour: &mut T) -> Result<(), UpdateTourError>
@@ -10,3 +10,52 @@ where
 {
     tour.apply(&tour_order!(0..tour.len()))
 }
+
+/// Repairs a (possibly partial) tour order so that it can be applied to the given tour.
+///
+/// Indices that are unknown to the tour, as well as repeated occurrences of an index, are
+/// dropped. The nodes which are missing from the order are then added one by one with the
+/// cheapest insertion method, i.e. each missing node is placed between the two consecutive
+/// nodes of the current order for which the increase in cost is minimal.
+///
+/// The operation will take O(N*M) time to complete, where M is the number of missing nodes.
+pub fn repair_tour_order<T>(tour: &T, order: &[usize]) -> TourOrder
+where
+    T: Tour,
+{
+    let len = tour.len();
+    let mut visited = vec![false; len];
+    let mut result = Vec::with_capacity(len);
+
+    for &idx in order {
+        if idx < len && !visited[idx] {
+            visited[idx] = true;
+            result.push(idx);
+        }
+    }
+
+    for (idx, _) in visited.into_iter().enumerate().filter(|(_, v)| !v) {
+        if result.len() < 2 {
+            result.push(idx);
+            continue;
+        }
+
+        let mut best_pos = 0;
+        let mut best_cost = Scalar::MAX;
+        for pos in 0..result.len() {
+            let (prev, next) = (result[pos], result[(pos + 1) % result.len()]);
+            let cost = tour.distance_at(&prev, &idx) + tour.distance_at(&idx, &next)
+                - tour.distance_at(&prev, &next);
+            if cost < best_cost {
+                best_cost = cost;
+                best_pos = pos + 1;
+            }
+        }
+
+        result.insert(best_pos, idx);
+    }
+
+    let mut to = TourOrder::with_ord(result);
+    to.set_cost(tour.measure(&to));
+    to
+}
@@ -1,4 +1,4 @@
-use std::{f64::consts::PI, ptr::NonNull};
+use std::{f64::consts::PI, sync::Arc};
 
 use crate::Scalar;
 
@@ -22,6 +22,7 @@ impl GetIndex for NodeIndex {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NodeIndex {
     index: usize,
     kind: NodeKind,
@@ -44,12 +45,53 @@ impl NodeIndex {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NodeKind {
     Depot,
     Target,
 }
 
-#[derive(Clone, Debug)]
+/// The nodes of an instance together with their coordinates, metadata and costs.
+///
+/// Tours created from a store share its nodes and costs with it. While any such tour exists,
+/// the store is frozen: [`add`](Self::add) returns ```None``` and [`compute`](Self::compute)
+/// returns ```false```, so that tours on other threads never observe a store being modified.
+///
+/// ```
+/// use cykl::{
+///     data::{DataStore, Metric, NodeKind},
+///     tour::{Array, Tour},
+/// };
+///
+/// let mut store = DataStore::new(Metric::Euc2d);
+/// store.add(NodeKind::Target, vec![0., 0.], ());
+/// store.add(NodeKind::Target, vec![3., 4.], ());
+///
+/// let tour = Array::new(&store);
+/// let copy = tour.clone();
+/// let handle = std::thread::spawn(move || copy.distance_at(&0, &1));
+///
+/// assert!(store.add(NodeKind::Target, vec![6., 8.], ()).is_none());
+/// assert_eq!(5., handle.join().unwrap());
+///
+/// drop(tour);
+/// assert!(store.add(NodeKind::Target, vec![6., 8.], ()).is_some());
+/// ```
+///
+/// Reading a store on one thread while modifying it on another does not compile:
+///
+/// ```compile_fail
+/// use cykl::data::{DataStore, Metric, NodeKind};
+///
+/// let mut store = DataStore::new(Metric::Euc2d);
+/// store.add(NodeKind::Target, vec![0., 0.], ());
+///
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| store.cost(&0, &0));
+///     scope.spawn(|| store.add(NodeKind::Target, vec![3., 4.], ()));
+/// });
+/// ```
+#[derive(Debug)]
 pub struct DataStore<M> {
     node: NodeStore,
     meta: Vec<M>,
@@ -57,22 +99,7 @@ pub struct DataStore<M> {
 
 impl<M> DataStore<M> {
     pub fn new(metric: Metric) -> Self {
-        let inner = InnerStore {
-            dim: metric.dim(),
-            metric,
-            nodes: Vec::new(),
-            coords: Vec::new(),
-            costs: Vec::with_capacity(0),
-        };
-
-        let node = NodeStore {
-            inner: NonNull::new(Box::leak(Box::new(inner))),
-        };
-
-        Self {
-            node,
-            meta: Vec::new(),
-        }
+        Self::with_capacity(metric, 0)
     }
 
     pub fn with_capacity(metric: Metric, capacity: usize) -> Self {
@@ -84,69 +111,71 @@ impl<M> DataStore<M> {
             costs: Vec::with_capacity(0),
         };
 
-        let node = NodeStore {
-            inner: NonNull::new(Box::leak(Box::new(inner))),
-        };
-
         Self {
-            node,
+            node: NodeStore {
+                inner: Arc::new(inner),
+            },
             meta: Vec::with_capacity(capacity),
         }
     }
 
     #[inline]
-    pub fn add(&mut self, kind: NodeKind, mut pos: Vec<f64>, meta: M) -> Option<NodeIndex> {
-        self.node.inner.and_then(|inner| unsafe {
-            if pos.len() != inner.as_ref().dim {
-                panic!("Len mismatched")
-            }
+    pub fn add(&mut self, kind: NodeKind, pos: Vec<f64>, meta: M) -> Option<NodeIndex> {
+        self.add_coords(kind, &pos, meta)
+    }
 
-            let idx = inner.as_ref().nodes.len();
-            let node = NodeIndex::new(idx, kind);
+    /// Adds a node whose coordinates are copied from the given slice.
+    ///
+    /// Returns ```None``` if the store is shared with a tour.
+    ///
+    /// # Panics
+    /// Panics if the length of `pos` does not match the dimension of the metric.
+    #[inline]
+    pub fn add_coords(&mut self, kind: NodeKind, pos: &[f64], meta: M) -> Option<NodeIndex> {
+        let inner = Arc::get_mut(&mut self.node.inner)?;
+        if pos.len() != inner.dim {
+            panic!("Len mismatched")
+        }
 
-            (*inner.as_ptr()).nodes.push(node);
-            (*inner.as_ptr()).coords.append(&mut pos);
-            self.meta.push(meta);
+        let node = NodeIndex::new(inner.nodes.len(), kind);
+        inner.nodes.push(node);
+        inner.coords.extend_from_slice(pos);
+        self.meta.push(meta);
 
-            inner.as_ref().nodes.get(idx).cloned()
-        })
+        Some(node)
     }
 
-    pub fn compute(&mut self) {
-        if let Some(inner) = self.node.inner {
-            unsafe {
-                let n_nodes = inner.as_ref().nodes.len();
-                let dim = inner.as_ref().dim;
-
-                let mut result = vec![0.; n_nodes * n_nodes];
-                inner
-                    .as_ref()
-                    .coords
-                    .chunks(dim)
-                    .enumerate()
-                    .for_each(|(idx1, x1)| {
-                        let tmp = idx1 * n_nodes;
-                        inner
-                            .as_ref()
-                            .coords
-                            .chunks(dim)
-                            .enumerate()
-                            .for_each(|(idx2, x2)| {
-                                let pos = tmp + idx2;
-                                if idx1 != idx2 {
-                                    result[pos] = inner.as_ref().metric.cost(x1, x2);
-                                }
-                            })
-                    });
-
-                (*inner.as_ptr()).costs = result;
-            }
-        }
+    /// Computes and stores the costs between all nodes.
+    ///
+    /// Returns ```false``` without computing anything if the store is shared with a tour.
+    pub fn compute(&mut self) -> bool {
+        let inner = match Arc::get_mut(&mut self.node.inner) {
+            Some(inner) => inner,
+            None => return false,
+        };
+
+        let n_nodes = inner.nodes.len();
+        let dim = inner.dim;
+
+        let mut result = vec![0.; n_nodes * n_nodes];
+        inner.coords.chunks(dim).enumerate().for_each(|(idx1, x1)| {
+            let tmp = idx1 * n_nodes;
+            inner.coords.chunks(dim).enumerate().for_each(|(idx2, x2)| {
+                let pos = tmp + idx2;
+                if idx1 != idx2 {
+                    result[pos] = inner.metric.cost(x1, x2);
+                }
+            })
+        });
+
+        inner.costs = result;
+        true
     }
 
+    /// Returns a handle to the nodes and costs, which freezes the store while it exists.
     #[inline]
     pub(crate) fn store(&self) -> NodeStore {
-        self.node
+        self.node.clone()
     }
 
     #[inline]
@@ -166,14 +195,156 @@ impl<M> DataStore<M> {
     {
         self.node.cost(a, b)
     }
+
+    /// Returns the metric with which costs between nodes are computed.
+    #[inline]
+    pub fn metric(&self) -> Metric {
+        self.node.metric()
+    }
+
+    /// Returns the number of coordinates of each node.
+    #[inline]
+    pub fn dim(&self) -> usize {
+        self.node.dim()
+    }
+
+    /// Returns the coordinates of a node.
+    ///
+    /// # Panics
+    /// Panics if the store does not contain the node.
+    #[inline]
+    pub fn coords<I>(&self, node: &I) -> &[Scalar]
+    where
+        I: GetIndex,
+    {
+        self.node.coords(node)
+    }
 }
 
-#[derive(Clone, Copy, Debug, Hash)]
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize)]
+struct DataStoreRef<'a, M> {
+    metric: Metric,
+    kinds: Vec<NodeKind>,
+    coords: &'a [Scalar],
+    meta: &'a [M],
+    computed: bool,
+    costs: Option<&'a [Scalar]>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct DataStoreOwned<M> {
+    metric: Metric,
+    kinds: Vec<NodeKind>,
+    coords: Vec<Scalar>,
+    meta: Vec<M>,
+    computed: bool,
+    #[serde(default)]
+    costs: Option<Vec<Scalar>>,
+}
+
+// The cost matrix is only serialised for explicit weights. Otherwise, it is recomputed from the
+// coordinates when a store is deserialised, which yields bitwise identical costs.
+#[cfg(feature = "serde")]
+impl<M> serde::Serialize for DataStore<M>
+where
+    M: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        let inner = &self.node.inner;
+        let repr = DataStoreRef {
+            metric: inner.metric,
+            kinds: inner.nodes.iter().map(|n| n.kind()).collect(),
+            coords: &inner.coords,
+            meta: &self.meta,
+            computed: !inner.costs.is_empty(),
+            costs: match inner.metric {
+                Metric::Explicit => Some(inner.costs.as_slice()),
+                _ => None,
+            },
+        };
+
+        repr.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, M> serde::Deserialize<'de> for DataStore<M>
+where
+    M: serde::Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let repr = DataStoreOwned::<M>::deserialize(deserializer)?;
+        if repr.metric == Metric::Custom {
+            return Err(D::Error::custom("custom metrics cannot be deserialized"));
+        }
+
+        let n_nodes = repr.kinds.len();
+        let dim = repr.metric.dim();
+        if n_nodes != repr.meta.len()
+            || n_nodes * dim != repr.coords.len()
+            || repr
+                .costs
+                .as_ref()
+                .is_some_and(|costs| !costs.is_empty() && costs.len() != n_nodes * n_nodes)
+        {
+            return Err(D::Error::custom("Len mismatched"));
+        }
+
+        let mut store = Self::with_capacity(repr.metric, repr.kinds.len());
+        let coords = if dim == 0 {
+            vec![Vec::with_capacity(0); repr.kinds.len()]
+        } else {
+            repr.coords.chunks(dim).map(|x| x.to_vec()).collect()
+        };
+
+        for ((kind, pos), meta) in repr.kinds.into_iter().zip(coords).zip(repr.meta) {
+            store.add(kind, pos, meta);
+        }
+
+        match repr.costs {
+            Some(costs) => Arc::get_mut(&mut store.node.inner).unwrap().costs = costs,
+            None if repr.computed => {
+                store.compute();
+            }
+            None => {}
+        }
+
+        Ok(store)
+    }
+}
+
+impl<M> Clone for DataStore<M>
+where
+    M: Clone,
+{
+    /// Returns a deep copy of the store, which is not shared with the tours of the original.
+    fn clone(&self) -> Self {
+        Self {
+            node: NodeStore {
+                inner: Arc::new((*self.node.inner).clone()),
+            },
+            meta: self.meta.clone(),
+        }
+    }
+}
+
+/// A shared handle to the nodes and costs of a [`DataStore`], through which tours read them.
+#[derive(Clone, Debug)]
 pub(crate) struct NodeStore {
-    inner: Option<NonNull<InnerStore>>,
+    inner: Arc<InnerStore>,
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 struct InnerStore {
     dim: usize,
     metric: Metric,
@@ -190,18 +361,31 @@ impl NodeStore {
     /// Returns the number of nodes registered in the store.
     #[inline]
     pub fn len(&self) -> usize {
-        match self.inner {
-            Some(inner) => unsafe { inner.as_ref().nodes.len() },
-            None => 0,
-        }
+        self.inner.nodes.len()
     }
 
     #[inline]
     pub fn is_empty(&self) -> bool {
-        match self.inner {
-            Some(inner) => unsafe { inner.as_ref().nodes.is_empty() },
-            None => true,
-        }
+        self.inner.nodes.is_empty()
+    }
+
+    #[inline]
+    pub fn metric(&self) -> Metric {
+        self.inner.metric
+    }
+
+    #[inline]
+    pub fn dim(&self) -> usize {
+        self.inner.dim
+    }
+
+    #[inline]
+    pub fn coords<I>(&self, node: &I) -> &[Scalar]
+    where
+        I: GetIndex,
+    {
+        let start = node.get() * self.inner.dim;
+        &self.inner.coords[start..start + self.inner.dim]
     }
 
     #[inline]
@@ -210,14 +394,20 @@ impl NodeStore {
         I: GetIndex + PartialEq + Eq,
     {
         if a == b {
-            0.
+            return 0.;
+        }
+
+        let inner = &*self.inner;
+        if inner.costs.is_empty() {
+            // Without a precomputed matrix, e.g. for very large instances, costs are computed
+            // from the coordinates on demand.
+            let (a, b) = (a.get() * inner.dim, b.get() * inner.dim);
+            inner.metric.cost(
+                &inner.coords[a..a + inner.dim],
+                &inner.coords[b..b + inner.dim],
+            )
         } else {
-            match self.inner {
-                Some(inner) => unsafe {
-                    inner.as_ref().costs[a.get() * inner.as_ref().nodes.len() + b.get()]
-                },
-                None => 0.,
-            }
+            inner.costs[a.get() * inner.nodes.len() + b.get()]
         }
     }
 }
@@ -228,14 +418,12 @@ impl<'s> IntoIterator for &'s NodeStore {
     type IntoIter = std::slice::Iter<'s, NodeIndex>;
 
     fn into_iter(self) -> Self::IntoIter {
-        match self.inner {
-            Some(inner) => unsafe { inner.as_ref().nodes.iter() },
-            None => todo!(),
-        }
+        self.inner.nodes.iter()
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Metric {
     /// Weights are explicitly given in the data file.
     Explicit,
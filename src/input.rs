@@ -0,0 +1,349 @@
+use getset::{CopyGetters, Getters};
+
+use crate::{
+    data::{Metric, NodeKind},
+    Scalar,
+};
+
+/// Errors which occur while building a [`Model`](crate::Model) or its candidates from external
+/// input.
+#[derive(Debug)]
+pub enum InputError {
+    /// The input could not be read.
+    Io(std::io::Error),
+
+    /// The metric does not compute costs from coordinates.
+    UnsupportedMetric(Metric),
+
+    /// The number of coordinate columns does not fit the dimension of the metric.
+    ColumnsMismatched { expected: usize, received: usize },
+
+    /// The number of nodes in the input does not match the number of nodes it is read for.
+    NodesMismatched { expected: usize, received: usize },
+
+    /// One or more records are invalid. All invalid records of the input are reported.
+    InvalidRecords(Vec<RecordError>),
+
+    /// The precision by which costs are scaled is zero, negative or not finite.
+    InvalidPrecision(Scalar),
+
+    /// The model must be completed with [`Model::complete`](crate::Model::complete) first.
+    IncompleteModel,
+}
+
+impl From<std::io::Error> for InputError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl std::fmt::Display for InputError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "failed to read input: {}", err),
+            Self::UnsupportedMetric(metric) => {
+                write!(f, "metric {:?} does not use coordinates", metric)
+            }
+            Self::ColumnsMismatched { expected, received } => write!(
+                f,
+                "expected {} coordinate columns, received {}",
+                expected, received
+            ),
+            Self::NodesMismatched { expected, received } => {
+                write!(f, "expected {} nodes, received {}", expected, received)
+            }
+            Self::InvalidRecords(errors) => {
+                write!(f, "{} invalid records", errors.len())?;
+                for err in errors {
+                    write!(f, "; {}", err)?;
+                }
+                Ok(())
+            }
+            Self::InvalidPrecision(precision) => {
+                write!(
+                    f,
+                    "precision must be positive and finite, got {}",
+                    precision
+                )
+            }
+            Self::IncompleteModel => write!(f, "the model has not been completed"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// An invalid record in the input.
+///
+/// ```line``` is 1-based for all inputs. For files, such as CSV and LKH files, it is the line
+/// number in the file, counting empty lines and headers. For points and flat coordinates, it is
+/// the position of the record.
+#[derive(Clone, Debug, PartialEq, CopyGetters, Getters)]
+pub struct RecordError {
+    #[getset(get_copy = "pub")]
+    line: usize,
+    #[getset(get = "pub")]
+    kind: RecordErrorKind,
+}
+
+impl RecordError {
+    pub(crate) fn new(line: usize, kind: RecordErrorKind) -> Self {
+        Self { line, kind }
+    }
+}
+
+impl std::fmt::Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "record {}: {}", self.line, self.kind)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RecordErrorKind {
+    /// The number of coordinates does not match the dimension of the metric.
+    DimMismatched { expected: usize, received: usize },
+
+    /// The record has no value at the given column.
+    MissingField { column: usize },
+
+    /// The value at the given column is not a number.
+    InvalidNumber { column: usize, value: String },
+
+    /// The value at the given column is either infinite or NaN.
+    NonFinite { column: usize },
+
+    /// The value is not a known node kind.
+    InvalidKind(String),
+
+    /// The id does not refer to a node, e.g. because it is out of range.
+    InvalidNodeId(String),
+
+    /// The id has already been used by a previous record, or earlier in the same list.
+    DuplicateId(String),
+
+    /// The node lists itself as one of its own candidates.
+    SelfCandidate(String),
+
+    /// The number of entries at the given column exceeds the number of nodes which can be
+    /// listed.
+    InvalidCount { column: usize, value: String },
+}
+
+impl std::fmt::Display for RecordErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DimMismatched { expected, received } => {
+                write!(
+                    f,
+                    "expected {} coordinates, received {}",
+                    expected, received
+                )
+            }
+            Self::MissingField { column } => write!(f, "missing value at column {}", column),
+            Self::InvalidNumber { column, value } => {
+                write!(f, "invalid number {:?} at column {}", value, column)
+            }
+            Self::NonFinite { column } => write!(f, "non-finite value at column {}", column),
+            Self::InvalidKind(kind) => write!(f, "invalid node kind {:?}", kind),
+            Self::InvalidNodeId(id) => write!(f, "invalid node id {:?}", id),
+            Self::DuplicateId(id) => write!(f, "duplicate node id {:?}", id),
+            Self::SelfCandidate(id) => write!(f, "node {:?} is its own candidate", id),
+            Self::InvalidCount { column, value } => {
+                write!(f, "too many entries {:?} at column {}", value, column)
+            }
+        }
+    }
+}
+
+/// Node data read from a CSV file, which is kept as the metadata of a node.
+#[derive(Clone, Debug, PartialEq, CopyGetters, Getters)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeRecord {
+    /// The id of the node as given in the file, or the position of the node if the file has
+    /// no id column.
+    #[getset(get = "pub")]
+    id: String,
+    /// The demand of the node, or 0 if the file has no demand column.
+    #[getset(get_copy = "pub")]
+    demand: Scalar,
+}
+
+/// Describes the layout of a CSV file. Columns are 0-based.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CsvConfig {
+    delimiter: char,
+    header: bool,
+    id: Option<usize>,
+    x: usize,
+    y: usize,
+    z: Option<usize>,
+    kind: Option<usize>,
+    demand: Option<usize>,
+}
+
+impl CsvConfig {
+    #[inline]
+    pub(crate) fn n_coords(&self) -> usize {
+        if self.z.is_some() {
+            3
+        } else {
+            2
+        }
+    }
+
+    /// Parses a single non-empty line.
+    pub(crate) fn parse(
+        &self,
+        line: &str,
+        pos: usize,
+        coords: &mut Vec<Scalar>,
+    ) -> Result<(NodeKind, NodeRecord), RecordErrorKind> {
+        let fields: Vec<&str> = line.split(self.delimiter).map(|f| f.trim()).collect();
+        let field = |column: usize| match fields.get(column) {
+            Some(value) if !value.is_empty() => Ok(*value),
+            _ => Err(RecordErrorKind::MissingField { column }),
+        };
+        let number = |column: usize| {
+            let value = field(column)?;
+            match value.parse::<Scalar>() {
+                Ok(x) if x.is_finite() => Ok(x),
+                Ok(_) => Err(RecordErrorKind::NonFinite { column }),
+                Err(_) => Err(RecordErrorKind::InvalidNumber {
+                    column,
+                    value: value.to_string(),
+                }),
+            }
+        };
+
+        coords.clear();
+        for column in [Some(self.x), Some(self.y), self.z].iter().flatten() {
+            coords.push(number(*column)?);
+        }
+
+        let kind = match self.kind {
+            Some(column) => match field(column)?.to_ascii_lowercase().as_str() {
+                "depot" | "d" => NodeKind::Depot,
+                "target" | "t" => NodeKind::Target,
+                _ => return Err(RecordErrorKind::InvalidKind(fields[column].to_string())),
+            },
+            None => NodeKind::Target,
+        };
+
+        let id = match self.id {
+            Some(column) => field(column)?.to_string(),
+            None => pos.to_string(),
+        };
+
+        let demand = match self.demand {
+            Some(column) => number(column)?,
+            None => 0.,
+        };
+
+        Ok((kind, NodeRecord { id, demand }))
+    }
+
+    #[inline]
+    pub(crate) fn has_header(&self) -> bool {
+        self.header
+    }
+}
+
+/// Builds a [`CsvConfig`].
+///
+/// By default, the file has a header line and its columns are separated by commas. The columns
+/// 0, 1 and 2 hold the id, the x and the y coordinate of a node, respectively.
+#[derive(Debug, Default)]
+pub struct CsvConfigBuilder {
+    delimiter: Option<char>,
+    header: Option<bool>,
+    id: Option<Option<usize>>,
+    x: Option<usize>,
+    y: Option<usize>,
+    z: Option<usize>,
+    kind: Option<usize>,
+    demand: Option<usize>,
+}
+
+impl CsvConfigBuilder {
+    pub fn new() -> Self {
+        Self {
+            ..Default::default()
+        }
+    }
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    /// Sets whether the first line of the file is a header, which is skipped.
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = Some(header);
+        self
+    }
+
+    /// Sets the id column. If ```None```, the position of a node is used as its id.
+    pub fn id(mut self, column: Option<usize>) -> Self {
+        self.id = Some(column);
+        self
+    }
+
+    pub fn x(mut self, column: usize) -> Self {
+        self.x = Some(column);
+        self
+    }
+
+    pub fn y(mut self, column: usize) -> Self {
+        self.y = Some(column);
+        self
+    }
+
+    /// Sets the column of the z coordinate, which is required by 3D metrics.
+    pub fn z(mut self, column: usize) -> Self {
+        self.z = Some(column);
+        self
+    }
+
+    /// Sets the column of the node kind, whose values are either ```depot``` (or ```d```) or
+    /// ```target``` (or ```t```), case-insensitively. Without this column, all nodes are
+    /// targets.
+    pub fn kind(mut self, column: usize) -> Self {
+        self.kind = Some(column);
+        self
+    }
+
+    pub fn demand(mut self, column: usize) -> Self {
+        self.demand = Some(column);
+        self
+    }
+
+    pub fn build(self) -> CsvConfig {
+        CsvConfig {
+            delimiter: self.delimiter.unwrap_or(','),
+            header: self.header.unwrap_or(true),
+            id: self.id.unwrap_or(Some(0)),
+            x: self.x.unwrap_or(1),
+            y: self.y.unwrap_or(2),
+            z: self.z,
+            kind: self.kind,
+            demand: self.demand,
+        }
+    }
+}
+
+/// Returns the number of coordinates per node for the given metric.
+pub(crate) fn coord_dim(metric: Metric) -> Result<usize, InputError> {
+    match metric {
+        Metric::Explicit | Metric::Custom | Metric::Undefined => {
+            Err(InputError::UnsupportedMetric(metric))
+        }
+        _ => Ok(metric.dim()),
+    }
+}
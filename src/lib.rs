@@ -12,4 +12,9 @@ pub use model::RunConfigBuilder;
 
 pub mod data;
 
+pub mod input;
+
+mod pool;
+pub use pool::SolutionPool;
+
 mod tests;
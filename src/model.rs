@@ -1,32 +1,49 @@
-use std::{collections::HashSet, path::Path};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufRead, BufReader},
+    path::Path,
+};
 
 use tspf::TspBuilder;
 
 use crate::{
-    alg::{cand_gen_nn, solvers::solve_greedy, SolverKind},
+    alg::{
+        cand_gen_nn, lkh::solve_lkh, read_candidates, repair_tour_order, solvers::solve_greedy,
+        CandidateSet, SolverKind,
+    },
     data::{DataStore, Metric, NodeIndex, NodeKind},
-    tour::{TourOrder, TwoLevelList},
+    input::{coord_dim, CsvConfig, InputError, NodeRecord, RecordError, RecordErrorKind},
+    tour::{TourImpltor, TourKind, TourOrder, UpdateTourError},
+    Scalar, SolutionPool,
 };
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 // Note: single depot only.
 pub struct Model<M> {
     complete: bool,
     groupsize: usize,
     store: DataStore<M>,
     depots: HashSet<usize>,
-    tours: Vec<TwoLevelList>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pool: SolutionPool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cands: Option<CandidateSet>,
 }
 
 impl<M> Model<M> {
     // new(metric, conf);
+    /// Creates an empty model. A `groupsize` of zero lets the tour choose its group size from
+    /// the number of nodes.
     pub fn new(metric: Metric, groupsize: usize) -> Self {
         Self {
             complete: false,
             groupsize,
             store: DataStore::new(metric),
             depots: HashSet::new(),
-            tours: Vec::with_capacity(0),
+            pool: SolutionPool::default(),
+            cands: None,
         }
     }
 
@@ -41,7 +58,8 @@ impl<M> Model<M> {
             groupsize,
             store: DataStore::with_capacity(metric, cap_nodes),
             depots: HashSet::with_capacity(cap_depots),
-            tours: Vec::with_capacity(0),
+            pool: SolutionPool::default(),
+            cands: None,
         }
     }
 
@@ -60,6 +78,71 @@ impl<M> Model<M> {
         self.store.is_empty()
     }
 
+    /// Returns the pool of the best distinct solutions found across all calls to
+    /// [`solve`](Self::solve).
+    #[inline]
+    pub fn pool(&self) -> &SolutionPool {
+        &self.pool
+    }
+
+    /// Replaces the solution pool, e.g. to change its size or diversity threshold.
+    ///
+    /// Solutions of the previous pool are offered to the new pool.
+    pub fn set_pool(&mut self, mut pool: SolutionPool) {
+        for order in self.pool.iter() {
+            pool.insert(order.clone());
+        }
+        self.pool = pool;
+    }
+
+    /// Returns the candidate set attached to the model, if any.
+    #[inline]
+    pub fn candidates(&self) -> Option<&CandidateSet> {
+        self.cands.as_ref()
+    }
+
+    /// Attaches a candidate set, which is used by all subsequent calls to
+    /// [`solve`](Self::solve) instead of generating nearest neighbours. Passing ```None```
+    /// returns to the generated candidates.
+    ///
+    /// The set must hold a list for each node of the model, otherwise
+    /// [`InputError::NodesMismatched`] is returned and the attached set is kept.
+    pub fn set_candidates(&mut self, cands: Option<CandidateSet>) -> Result<(), InputError> {
+        if let Some(cands) = &cands {
+            if cands.len() != self.store.len() {
+                return Err(InputError::NodesMismatched {
+                    expected: self.store.len(),
+                    received: cands.len(),
+                });
+            }
+        }
+
+        self.cands = cands;
+        Ok(())
+    }
+
+    /// Reads a candidate set in the format of the `CANDIDATE_FILE` of LKH and attaches it to
+    /// the model, replacing any attached set. See [`read_candidates`] for the format and
+    /// `precision`.
+    ///
+    /// The attached set can be written back with
+    /// [`write_candidates`](crate::alg::write_candidates).
+    ///
+    /// The model must already be [complete](Self::complete), otherwise
+    /// [`InputError::IncompleteModel`] is returned.
+    pub fn read_candidates<R>(&mut self, reader: R, precision: Scalar) -> Result<(), InputError>
+    where
+        R: BufRead,
+    {
+        if !self.complete {
+            return Err(InputError::IncompleteModel);
+        }
+
+        let tour = TourImpltor::new(TourKind::Array, &self.store, self.groupsize);
+        self.cands = Some(read_candidates(&tour, reader, precision)?);
+        Ok(())
+    }
+
     #[inline]
     pub fn complete(&mut self) {
         if !self.complete {
@@ -68,11 +151,71 @@ impl<M> Model<M> {
         }
     }
 
+    /// Creates a model from an iterator of coordinates and metadata. All nodes are targets.
+    ///
+    /// Every item whose number of coordinates does not match the metric or which contains
+    /// non-finite coordinates is reported in [`InputError::InvalidRecords`], together with its
+    /// 1-based position.
+    pub fn from_points<I, P>(
+        metric: Metric,
+        groupsize: usize,
+        points: I,
+    ) -> Result<Self, InputError>
+    where
+        I: IntoIterator<Item = (P, M)>,
+        P: AsRef<[f64]>,
+    {
+        let dim = coord_dim(metric)?;
+        let points = points.into_iter();
+
+        let mut model = Self::with_capacity(metric, groupsize, 0, points.size_hint().0);
+        let mut errors = Vec::new();
+
+        for (idx, (pos, meta)) in points.enumerate() {
+            let pos = pos.as_ref();
+            if let Err(kind) = check_coords(pos, dim) {
+                errors.push(RecordError::new(idx + 1, kind));
+            } else if errors.is_empty() {
+                model.add_coords(NodeKind::Target, pos, meta);
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(model)
+        } else {
+            Err(InputError::InvalidRecords(errors))
+        }
+    }
+
+    /// Creates a model from a flat slice of coordinates, where each consecutive chunk of
+    /// `metric.dim()` values belongs to one node. All nodes are targets with default metadata.
+    ///
+    /// Invalid chunks are reported as in [`from_points`](Self::from_points).
+    pub fn from_flat(metric: Metric, groupsize: usize, coords: &[f64]) -> Result<Self, InputError>
+    where
+        M: Default,
+    {
+        let dim = coord_dim(metric)?;
+        let chunks = coords.chunks(dim).map(|pos| (pos, M::default()));
+
+        Self::from_points(metric, groupsize, chunks)
+    }
+
     pub fn add(&mut self, kind: NodeKind, pos: Vec<f64>, meta: M) -> Option<NodeIndex> {
+        self.add_coords(kind, &pos, meta)
+    }
+
+    /// Adds a node whose coordinates are copied from the given slice.
+    ///
+    /// Returns ```None``` if the model is already complete.
+    ///
+    /// # Panics
+    /// Panics if the length of `pos` does not match the dimension of the metric.
+    pub fn add_coords(&mut self, kind: NodeKind, pos: &[f64], meta: M) -> Option<NodeIndex> {
         if self.complete {
             None
         } else {
-            let node = self.store.add(kind, pos, meta);
+            let node = self.store.add_coords(kind, pos, meta);
             if let Some(x) = &node {
                 if kind == NodeKind::Depot {
                     self.depots.insert(x.index());
@@ -82,31 +225,174 @@ impl<M> Model<M> {
         }
     }
 
-    // TODO: should return status and/or result.
-    pub fn solve(&mut self, config: &RunConfig) -> Option<TourOrder> {
+    /// Solves the model and returns the best tour order found, or ```None``` if the solver
+    /// found no tour, e.g. because the model has no nodes.
+    ///
+    /// Returns an error if the solver fails, e.g. with
+    /// [`UpdateTourError::UnsupportedMove`] for a k-opt move that is not supported yet, or with
+    /// [`UpdateTourError::TourLenMismatched`] if nodes were added after attaching a candidate
+    /// set.
+    pub fn solve(&mut self, config: &RunConfig) -> Result<Option<TourOrder>, UpdateTourError> {
         self.complete();
+        if self.store.is_empty() {
+            return Ok(None);
+        }
 
-        let mut tour = TwoLevelList::new(&self.store, self.groupsize);
-        cand_gen_nn(&mut tour, config.cands);
+        let mut tour = TourImpltor::new(config.tour, &self.store, self.groupsize);
+        let generated;
+        let cands = match &self.cands {
+            Some(cands) if cands.len() != self.store.len() => {
+                return Err(UpdateTourError::TourLenMismatched {
+                    expected: self.store.len(),
+                    received: cands.len(),
+                })
+            }
+            Some(cands) => cands,
+            None => {
+                generated = cand_gen_nn(&tour, config.cands);
+                &generated
+            }
+        };
 
         let result = match config.solver {
-            SolverKind::Greedy(ref starters) => solve_greedy(&mut tour, starters),
+            SolverKind::Greedy(ref starters) => solve_greedy(&mut tour, cands, starters),
+            SolverKind::Lkh { kopt, trials } => {
+                let init = config
+                    .init_order
+                    .as_ref()
+                    .map(|order| repair_tour_order(&tour, order));
+                solve_lkh(&mut tour, cands, kopt, trials, init.as_ref()).map(Some)
+            }
         };
 
-        result.unwrap()
+        let result = result?;
+        if let Some(order) = &result {
+            self.pool.insert(order.clone());
+        }
+
+        Ok(result)
+    }
+
+    /// Solves the model starting from the solution at the given rank of the pool.
+    ///
+    /// The initial order of ```config``` is replaced by the pooled solution. Returns
+    /// ```Ok(None)``` if the pool holds no solution at that rank.
+    pub fn reseed(
+        &mut self,
+        config: &RunConfig,
+        rank: usize,
+    ) -> Result<Option<TourOrder>, UpdateTourError> {
+        let init = match self.pool.get(rank) {
+            Some(order) => order.order().clone(),
+            None => return Ok(None),
+        };
+        let config = RunConfig {
+            init_order: Some(init),
+            ..config.clone()
+        };
+        self.solve(&config)
+    }
+}
+
+impl Model<NodeRecord> {
+    /// Creates a model from a CSV file with the given layout.
+    pub fn from_csv<P>(
+        path: P,
+        metric: Metric,
+        groupsize: usize,
+        config: &CsvConfig,
+    ) -> Result<Self, InputError>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path)?;
+        Self::from_csv_reader(BufReader::new(file), metric, groupsize, config)
+    }
+
+    /// Creates a model from CSV data with the given layout.
+    ///
+    /// Empty lines are skipped. All invalid lines are collected and reported together in
+    /// [`InputError::InvalidRecords`], including lines with duplicated ids.
+    pub fn from_csv_reader<R>(
+        reader: R,
+        metric: Metric,
+        groupsize: usize,
+        config: &CsvConfig,
+    ) -> Result<Self, InputError>
+    where
+        R: BufRead,
+    {
+        let dim = coord_dim(metric)?;
+        if dim != config.n_coords() {
+            return Err(InputError::ColumnsMismatched {
+                expected: dim,
+                received: config.n_coords(),
+            });
+        }
+
+        let mut model = Self::new(metric, groupsize);
+        let mut errors = Vec::new();
+        let mut ids = HashMap::new();
+        let mut coords = Vec::with_capacity(dim);
+
+        let skip = if config.has_header() { 1 } else { 0 };
+        for (idx, line) in reader.lines().enumerate().skip(skip) {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let pos = ids.len() + errors.len();
+            match config.parse(&line, pos, &mut coords) {
+                Ok((kind, record)) => {
+                    if ids.insert(record.id().clone(), idx + 1).is_some() {
+                        let kind = RecordErrorKind::DuplicateId(record.id().clone());
+                        errors.push(RecordError::new(idx + 1, kind));
+                    } else if errors.is_empty() {
+                        model.add_coords(kind, &coords, record);
+                    }
+                }
+                Err(kind) => errors.push(RecordError::new(idx + 1, kind)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(model)
+        } else {
+            Err(InputError::InvalidRecords(errors))
+        }
+    }
+}
+
+fn check_coords(pos: &[f64], dim: usize) -> Result<(), RecordErrorKind> {
+    if pos.len() != dim {
+        return Err(RecordErrorKind::DimMismatched {
+            expected: dim,
+            received: pos.len(),
+        });
+    }
+
+    match pos.iter().position(|x| !x.is_finite()) {
+        Some(column) => Err(RecordErrorKind::NonFinite { column }),
+        None => Ok(()),
     }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RunConfig {
     cands: usize,
     solver: SolverKind,
+    tour: TourKind,
+    init_order: Option<Vec<usize>>,
 }
 
 #[derive(Debug, Default)]
 pub struct RunConfigBuilder {
     cands: Option<usize>,
     solver: Option<SolverKind>,
+    tour: Option<TourKind>,
+    init_order: Option<Vec<usize>>,
 }
 
 impl RunConfigBuilder {
@@ -126,10 +412,32 @@ impl RunConfigBuilder {
         self
     }
 
+    /// Sets the backend of the tour. By default, the backend is chosen based on the number of
+    /// nodes (see [`TourKind::resolve`]).
+    pub fn tour(mut self, tour: TourKind) -> Self {
+        self.tour = Some(tour);
+        self
+    }
+
+    /// Sets the tour order from which the LKH solver starts, e.g. a solution of a previous run.
+    ///
+    /// The order may be partial or cover a different set of nodes. Before solving, nodes that
+    /// are unknown to the model are dropped and missing nodes are inserted at their cheapest
+    /// positions (see [`repair_tour_order`]).
+    ///
+    /// Only [`SolverKind::Lkh`] starts from this order. [`SolverKind::Greedy`] ignores it and
+    /// constructs its tours from its starting nodes.
+    pub fn init_order(mut self, order: &TourOrder) -> Self {
+        self.init_order = Some(order.order().clone());
+        self
+    }
+
     pub fn build(self) -> RunConfig {
         RunConfig {
             cands: self.cands.unwrap_or(10),
             solver: self.solver.unwrap_or_else(|| SolverKind::Greedy(vec![0])),
+            tour: self.tour.unwrap_or_default(),
+            init_order: self.init_order,
         }
     }
 }
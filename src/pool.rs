@@ -0,0 +1,182 @@
+use crate::tour::TourOrder;
+
+/// A bounded collection of the best distinct tour orders.
+///
+/// Two tour orders are considered distinct if their edge distance, i.e. the number of edges in
+/// one tour which do not appear in the other tour, is at least `min_distance`. The orders in a
+/// pool are kept in ascending order of their costs.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "PoolRepr"))]
+pub struct SolutionPool {
+    capacity: usize,
+    min_distance: usize,
+    orders: Vec<TourOrder>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct PoolRepr {
+    capacity: usize,
+    min_distance: usize,
+    orders: Vec<TourOrder>,
+}
+
+// A deserialised pool is created through `new` and must satisfy the invariants which `insert`
+// maintains, since its orders are not offered to the pool one by one.
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<PoolRepr> for SolutionPool {
+    type Error = &'static str;
+
+    fn try_from(repr: PoolRepr) -> Result<Self, Self::Error> {
+        let mut pool = Self::new(repr.capacity, repr.min_distance);
+        let orders = repr.orders;
+
+        if orders.len() > pool.capacity {
+            return Err("the pool holds more tour orders than its capacity");
+        }
+        if orders.iter().any(TourOrder::is_empty) {
+            return Err("the pool holds an empty tour order");
+        }
+        if orders
+            .windows(2)
+            .any(|pair| pair[0].cost() > pair[1].cost())
+        {
+            return Err("the tour orders of the pool are not sorted by cost");
+        }
+        for (idx, order) in orders.iter().enumerate() {
+            if orders[idx + 1..]
+                .iter()
+                .any(|other| order.edge_distance(other) < pool.min_distance)
+            {
+                return Err("the pool holds tour orders which are too similar");
+            }
+        }
+
+        pool.orders = orders;
+        Ok(pool)
+    }
+}
+
+impl SolutionPool {
+    /// Creates a new pool which holds at most `capacity` tour orders whose pairwise edge
+    /// distances are at least `min_distance`.
+    pub fn new(capacity: usize, min_distance: usize) -> Self {
+        Self {
+            capacity,
+            min_distance: min_distance.max(1),
+            orders: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Returns the maximum number of tour orders in the pool.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the minimum edge distance between any two tour orders in the pool.
+    #[inline]
+    pub fn min_distance(&self) -> usize {
+        self.min_distance
+    }
+
+    /// Returns the number of tour orders in the pool.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.orders.len()
+    }
+
+    /// Returns ```true``` if the pool contains no tour orders.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    /// Returns the tour order with the lowest cost.
+    #[inline]
+    pub fn best(&self) -> Option<&TourOrder> {
+        self.orders.first()
+    }
+
+    /// Returns the tour order at the given rank, where rank 0 is the best tour order.
+    #[inline]
+    pub fn get(&self, rank: usize) -> Option<&TourOrder> {
+        self.orders.get(rank)
+    }
+
+    /// Returns an iterator over all tour orders in ascending order of their costs.
+    #[inline]
+    pub fn iter(&self) -> std::slice::Iter<'_, TourOrder> {
+        self.orders.iter()
+    }
+
+    /// Returns a copy of all tour orders in ascending order of their costs.
+    pub fn export(&self) -> Vec<TourOrder> {
+        self.orders.clone()
+    }
+
+    /// Removes all tour orders from the pool.
+    pub fn clear(&mut self) {
+        self.orders.clear();
+    }
+
+    /// Offers a tour order to the pool and returns ```true``` if it is accepted.
+    ///
+    /// A tour order is rejected if the pool already holds an order which is too similar to it
+    /// and whose cost is lower or equal. Otherwise, the new order replaces all similar orders.
+    /// If the pool is full afterwards, the worst order is dropped.
+    pub fn insert(&mut self, order: TourOrder) -> bool {
+        if self.capacity == 0 || order.is_empty() {
+            return false;
+        }
+
+        let mut similar = Vec::new();
+        for (idx, other) in self.orders.iter().enumerate() {
+            if order.edge_distance(other) < self.min_distance {
+                if other.cost() <= order.cost() {
+                    return false;
+                }
+                similar.push(idx);
+            }
+        }
+
+        for idx in similar.into_iter().rev() {
+            self.orders.remove(idx);
+        }
+
+        if self.orders.len() == self.capacity {
+            match self.orders.last() {
+                Some(worst) if worst.cost() <= order.cost() => return false,
+                _ => {
+                    self.orders.pop();
+                }
+            }
+        }
+
+        let pos = self
+            .orders
+            .iter()
+            .position(|other| other.cost() > order.cost())
+            .unwrap_or(self.orders.len());
+        self.orders.insert(pos, order);
+
+        true
+    }
+}
+
+impl Default for SolutionPool {
+    fn default() -> Self {
+        Self::new(10, 1)
+    }
+}
+
+impl<'s> IntoIterator for &'s SolutionPool {
+    type Item = &'s TourOrder;
+
+    type IntoIter = std::slice::Iter<'s, TourOrder>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.orders.iter()
+    }
+}
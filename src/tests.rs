@@ -15,7 +15,7 @@ fn test_metric() {
 
     for ii in 0..len {
         for jj in 0..len {
-            let exp = Metric::Euc2d.cost(&vec![ii as f64; 2], &vec![jj as f64; 2]);
+            let exp = Metric::Euc2d.cost(&[ii as f64; 2], &[jj as f64; 2]);
             let res = ds.cost(&ii, &jj);
             assert!(
                 approx_eq!(f64, exp, res),
@@ -28,3 +28,531 @@ fn test_metric() {
         }
     }
 }
+
+#[test]
+fn test_warm_start() {
+    use crate::{alg::lkh::KOpt, alg::SolverKind, tour::TourOrder, Model, RunConfigBuilder};
+
+    let mut model = Model::<()>::new(Metric::Euc2d, 5);
+    for ii in 0..25 {
+        let (x, y) = ((ii * 7 % 13) as f64, (ii * 5 % 11) as f64);
+        model.add(NodeKind::Target, vec![x, y], ());
+    }
+
+    let solver = SolverKind::Lkh {
+        kopt: KOpt::Opt2,
+        trials: 1,
+    };
+
+    let config = RunConfigBuilder::new().solver(solver.clone()).build();
+    let yesterday = model.solve(&config).unwrap().unwrap();
+
+    // Yesterday's route misses the last node and contains a stop which no longer exists.
+    let mut order: Vec<usize> = yesterday
+        .order()
+        .iter()
+        .copied()
+        .filter(|&idx| idx != 24)
+        .collect();
+    order.push(99);
+
+    let config = RunConfigBuilder::new()
+        .solver(solver)
+        .init_order(&TourOrder::with_ord(order))
+        .build();
+    let today = model.solve(&config).unwrap().unwrap();
+
+    let mut visited = today.order().clone();
+    visited.sort_unstable();
+    assert_eq!((0..25).collect::<Vec<_>>(), visited);
+    assert!(today.cost() <= yesterday.cost() + 1e-9);
+}
+
+#[test]
+fn test_unsupported_kopt() {
+    use crate::{alg::lkh::KOpt, alg::SolverKind, tour::UpdateTourError, Model, RunConfigBuilder};
+
+    let mut model = Model::<()>::new(Metric::Euc2d, 5);
+    for ii in 0..10 {
+        model.add(NodeKind::Target, vec![ii as f64, 0.], ());
+    }
+
+    let config = RunConfigBuilder::new()
+        .solver(SolverKind::Lkh {
+            kopt: KOpt::Opt3,
+            trials: 1,
+        })
+        .build();
+    assert!(matches!(
+        model.solve(&config),
+        Err(UpdateTourError::UnsupportedMove)
+    ));
+    assert!(model.pool().is_empty());
+}
+
+#[test]
+fn test_empty_model() {
+    use crate::{
+        alg::lkh::KOpt, alg::SolverKind, tour::TourOrder, Model, RunConfig, RunConfigBuilder,
+    };
+
+    let lkh = SolverKind::Lkh {
+        kopt: KOpt::Opt2,
+        trials: 1,
+    };
+    let configs: Vec<RunConfig> = vec![
+        RunConfigBuilder::new().build(),
+        RunConfigBuilder::new().solver(lkh.clone()).build(),
+        RunConfigBuilder::new()
+            .solver(lkh)
+            .init_order(&TourOrder::with_ord(vec![0, 1]))
+            .build(),
+    ];
+
+    for config in &configs {
+        let mut model = Model::<()>::new(Metric::Euc2d, 5);
+        assert!(matches!(model.solve(config), Ok(None)));
+        assert!(model.pool().is_empty());
+    }
+}
+
+#[test]
+fn test_solution_pool() {
+    use crate::{tour::TourOrder, SolutionPool};
+
+    let mut pool = SolutionPool::new(2, 2);
+
+    assert!(pool.insert(TourOrder::with_cost(vec![0, 1, 2, 3, 4, 5], 10.)));
+    // Same cycle, rotated and reversed, but worse.
+    assert!(!pool.insert(TourOrder::with_cost(vec![3, 2, 1, 0, 5, 4], 12.)));
+    // Same cycle and better, thus replaces the previous one.
+    assert!(pool.insert(TourOrder::with_cost(vec![2, 3, 4, 5, 0, 1], 9.)));
+    assert_eq!(1, pool.len());
+    assert_eq!(9., pool.best().unwrap().cost());
+
+    assert!(pool.insert(TourOrder::with_cost(vec![0, 2, 4, 1, 3, 5], 15.)));
+    assert!(pool.insert(TourOrder::with_cost(vec![0, 3, 1, 4, 2, 5], 11.)));
+    assert_eq!(2, pool.len());
+    assert!(!pool.insert(TourOrder::with_cost(vec![0, 4, 2, 5, 3, 1], 20.)));
+
+    let costs: Vec<_> = pool.iter().map(|order| order.cost()).collect();
+    assert_eq!(vec![9., 11.], costs);
+    assert_eq!(&vec![0, 3, 1, 4, 2, 5], pool.get(1).unwrap().order());
+    assert_eq!(2, pool.export().len());
+}
+
+#[test]
+fn test_model_pool() {
+    use crate::{alg::lkh::KOpt, alg::SolverKind, Model, RunConfigBuilder, SolutionPool};
+
+    let mut model = Model::<()>::new(Metric::Euc2d, 5);
+    for ii in 0..25 {
+        let (x, y) = ((ii * 7 % 13) as f64, (ii * 5 % 11) as f64);
+        model.add(NodeKind::Target, vec![x, y], ());
+    }
+    model.set_pool(SolutionPool::new(3, 4));
+    assert!(model.pool().is_empty());
+    assert!(model
+        .reseed(&RunConfigBuilder::new().build(), 0)
+        .unwrap()
+        .is_none());
+
+    for starter in 0..5 {
+        let config = RunConfigBuilder::new()
+            .solver(SolverKind::Greedy(vec![starter]))
+            .build();
+        model.solve(&config).unwrap();
+    }
+
+    let pool = model.pool();
+    assert!(!pool.is_empty() && pool.len() <= 3);
+    assert!(pool
+        .iter()
+        .zip(pool.iter().skip(1))
+        .all(|(a, b)| a.cost() <= b.cost()));
+
+    let best = pool.best().unwrap().cost();
+    let config = RunConfigBuilder::new()
+        .solver(SolverKind::Lkh {
+            kopt: KOpt::Opt2,
+            trials: 1,
+        })
+        .build();
+    let result = model.reseed(&config, 0).unwrap().unwrap();
+    assert!(result.cost() <= best + 1e-9);
+    assert!(model.pool().best().unwrap().cost() <= result.cost());
+}
+
+#[test]
+fn test_model_candidates() {
+    use crate::{
+        alg::{write_candidates, CandidateScore, CandidateSet, SolverKind},
+        input::InputError,
+        tour::{Tour, TwoLevelList, UpdateTourError},
+        Model, RunConfigBuilder,
+    };
+
+    let mut model = Model::<()>::new(Metric::Euc2d, 5);
+    for ii in 0..9 {
+        model.add(NodeKind::Target, vec![ii as f64, 0.], ());
+    }
+
+    // Reading candidates does not complete the model.
+    assert!(matches!(
+        model.read_candidates("9\n".as_bytes(), 100.),
+        Err(InputError::IncompleteModel)
+    ));
+    assert!(model.add(NodeKind::Target, vec![9., 0.], ()).is_some());
+
+    // Each node only has the node three steps ahead as its candidate.
+    let mut store = DataStore::<()>::with_capacity(Metric::Euc2d, 10);
+    (0..10).for_each(|ii| {
+        store.add(NodeKind::Target, vec![ii as f64, 0.], ());
+    });
+    let tour = TwoLevelList::new(&store, 5);
+    let cands: CandidateSet = tour
+        .itr()
+        .map(|node| {
+            let next = tour.get((node.index() + 3) % 10).unwrap();
+            vec![(next, CandidateScore::default())]
+        })
+        .collect();
+
+    // A set for a different number of nodes is rejected.
+    let short: CandidateSet = (0..9).map(|_| Vec::new()).collect();
+    assert!(matches!(
+        model.set_candidates(Some(short)),
+        Err(InputError::NodesMismatched {
+            expected: 10,
+            received: 9
+        })
+    ));
+    assert!(model.candidates().is_none());
+
+    model.set_candidates(Some(cands)).unwrap();
+    assert_eq!(Some(10), model.candidates().map(|cands| cands.len()));
+
+    let config = RunConfigBuilder::new()
+        .solver(SolverKind::Greedy(vec![0]))
+        .build();
+    let result = model.solve(&config).unwrap().unwrap();
+    assert_eq!(&vec![0, 3, 6, 9, 2, 5, 8, 1, 4, 7], result.order());
+
+    let mut file = Vec::new();
+    write_candidates(&mut file, model.candidates().unwrap(), 100.).unwrap();
+    model.set_candidates(None).unwrap();
+    model.read_candidates(file.as_slice(), 100.).unwrap();
+    let result = model.solve(&config).unwrap().unwrap();
+    assert_eq!(&vec![0, 3, 6, 9, 2, 5, 8, 1, 4, 7], result.order());
+
+    model.set_candidates(None).unwrap();
+    assert!(model.candidates().is_none());
+    let result = model.solve(&config).unwrap().unwrap();
+    assert_ne!(&vec![0, 3, 6, 9, 2, 5, 8, 1, 4, 7], result.order());
+
+    // Nodes added after attaching a set make it unusable.
+    let mut model = Model::<()>::new(Metric::Euc2d, 5);
+    model.add(NodeKind::Target, vec![0., 0.], ());
+    model
+        .set_candidates(Some(std::iter::once(Vec::new()).collect()))
+        .unwrap();
+    model.add(NodeKind::Target, vec![1., 0.], ());
+    assert!(matches!(
+        model.solve(&config),
+        Err(UpdateTourError::TourLenMismatched {
+            expected: 2,
+            received: 1
+        })
+    ));
+}
+
+#[test]
+fn test_model_from_points() {
+    use crate::{
+        input::{InputError, RecordErrorKind},
+        Model,
+    };
+
+    let points = vec![([0., 0.], 'a'), ([3., 4.], 'b'), ([6., 0.], 'c')];
+    let model = Model::from_points(Metric::Euc2d, 2, points).unwrap();
+    assert_eq!(3, model.n_nodes());
+    assert_eq!(0, model.n_depots());
+
+    let model = Model::<()>::from_flat(Metric::Euc3d, 2, &[0., 0., 0., 1., 2., 3.]).unwrap();
+    assert_eq!(2, model.n_nodes());
+
+    let points = vec![
+        (vec![0., 0.], ()),
+        (vec![1.], ()),
+        (vec![2., 2.], ()),
+        (vec![3., f64::NAN], ()),
+    ];
+    // Records are counted from 1, like the lines of a CSV file.
+    match Model::from_points(Metric::Euc2d, 2, points) {
+        Err(InputError::InvalidRecords(errors)) => {
+            assert_eq!(2, errors.len());
+            assert_eq!(2, errors[0].line());
+            assert_eq!(
+                &RecordErrorKind::DimMismatched {
+                    expected: 2,
+                    received: 1
+                },
+                errors[0].kind()
+            );
+            assert_eq!(4, errors[1].line());
+            assert_eq!(&RecordErrorKind::NonFinite { column: 1 }, errors[1].kind());
+        }
+        _ => panic!("Invalid points are accepted"),
+    }
+
+    match Model::<()>::from_flat(Metric::Euc2d, 2, &[0., 0., 1.]) {
+        Err(InputError::InvalidRecords(errors)) => assert_eq!(2, errors[0].line()),
+        _ => panic!("Incomplete coordinates are accepted"),
+    }
+
+    assert!(matches!(
+        Model::<()>::from_flat(Metric::Explicit, 2, &[0., 0.]),
+        Err(InputError::UnsupportedMetric(Metric::Explicit))
+    ));
+}
+
+#[test]
+fn test_model_from_csv() {
+    use crate::{
+        input::{CsvConfigBuilder, InputError, RecordErrorKind},
+        Model,
+    };
+
+    let data = "x;y;kind;name\n1;2;Depot;a\n\n3;4;t;b\n5;6;target;c\n";
+    let config = CsvConfigBuilder::new()
+        .delimiter(';')
+        .x(0)
+        .y(1)
+        .kind(2)
+        .id(Some(3))
+        .build();
+    let model = Model::from_csv_reader(data.as_bytes(), Metric::Euc2d, 2, &config).unwrap();
+    assert_eq!(3, model.n_nodes());
+    assert_eq!(1, model.n_depots());
+
+    let data = "1,2\n3,x\n5\n7,8\n1,2\n";
+    let config = CsvConfigBuilder::new()
+        .header(false)
+        .id(None)
+        .x(0)
+        .y(1)
+        .build();
+    let model = Model::from_csv_reader(data.as_bytes(), Metric::Euc2d, 2, &config).unwrap_err();
+    match model {
+        InputError::InvalidRecords(errors) => {
+            let errors: Vec<_> = errors
+                .iter()
+                .map(|e| (e.line(), e.kind().clone()))
+                .collect();
+            assert_eq!(
+                vec![
+                    (
+                        2,
+                        RecordErrorKind::InvalidNumber {
+                            column: 1,
+                            value: "x".to_string()
+                        }
+                    ),
+                    (3, RecordErrorKind::MissingField { column: 1 }),
+                ],
+                errors
+            );
+        }
+        _ => panic!("Invalid lines are accepted"),
+    }
+
+    let data = "id,x,y,kind\n0,1,2,depot\n1,3,4,hub\n0,5,6,t\n";
+    let config = CsvConfigBuilder::new().kind(3).build();
+    match Model::from_csv_reader(data.as_bytes(), Metric::Euc2d, 2, &config) {
+        Err(InputError::InvalidRecords(errors)) => {
+            assert_eq!(2, errors.len());
+            assert_eq!(3, errors[0].line());
+            assert_eq!(
+                &RecordErrorKind::InvalidKind("hub".to_string()),
+                errors[0].kind()
+            );
+            assert_eq!(4, errors[1].line());
+            assert_eq!(
+                &RecordErrorKind::DuplicateId("0".to_string()),
+                errors[1].kind()
+            );
+        }
+        _ => panic!("Invalid kind is accepted"),
+    }
+
+    let config = CsvConfigBuilder::new().build();
+    assert!(matches!(
+        Model::from_csv_reader("".as_bytes(), Metric::Euc3d, 2, &config),
+        Err(InputError::ColumnsMismatched {
+            expected: 3,
+            received: 2
+        })
+    ));
+}
+
+#[test]
+fn test_tour_kinds() {
+    use crate::{alg::lkh::KOpt, alg::SolverKind, tour::TourKind, Model, RunConfigBuilder};
+
+    let mut model = Model::<()>::new(Metric::Euc2d, 5);
+    for ii in 0..25 {
+        let (x, y) = ((ii * 7 % 13) as f64, (ii * 5 % 11) as f64);
+        model.add(NodeKind::Target, vec![x, y], ());
+    }
+
+    for &kind in &[
+        TourKind::Auto,
+        TourKind::Array,
+        TourKind::TwoLevelList,
+        TourKind::SplayTree,
+    ] {
+        let config = RunConfigBuilder::new()
+            .solver(SolverKind::Lkh {
+                kopt: KOpt::Opt2,
+                trials: 1,
+            })
+            .tour(kind)
+            .build();
+        let result = model.solve(&config).unwrap().unwrap();
+
+        let mut visited = result.order().clone();
+        visited.sort_unstable();
+        assert_eq!((0..25).collect::<Vec<_>>(), visited, "Tour kind {:?}", kind);
+    }
+}
+
+#[cfg(feature = "serde")]
+mod test_serde {
+    use crate::{
+        alg::SolverKind,
+        data::{DataStore, Metric, NodeKind},
+        tour::TourOrder,
+        Model, RunConfig, RunConfigBuilder,
+    };
+
+    fn create_model() -> Model<String> {
+        let mut model = Model::with_capacity(Metric::Euc2d, 4, 1, 20);
+        for ii in 0..20 {
+            let kind = if ii == 0 {
+                NodeKind::Depot
+            } else {
+                NodeKind::Target
+            };
+            let (x, y) = ((ii * 7 % 13) as f64, (ii * 5 % 11) as f64);
+            model.add(kind, vec![x, y], format!("node-{}", ii));
+        }
+        model
+    }
+
+    fn assert_same_solution(a: &TourOrder, b: &TourOrder) {
+        assert_eq!(a.order(), b.order());
+        assert_eq!(a.cost().to_bits(), b.cost().to_bits());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut model = create_model();
+        let config = RunConfigBuilder::new()
+            .cands(5)
+            .solver(SolverKind::Greedy(vec![0, 3]))
+            .build();
+
+        let json = serde_json::to_string(&model).unwrap();
+        let mut reloaded: Model<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(model.n_nodes(), reloaded.n_nodes());
+        assert_eq!(model.n_depots(), reloaded.n_depots());
+
+        let json = serde_json::to_string(&config).unwrap();
+        let config_reloaded: RunConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(config, config_reloaded);
+
+        let expected = model.solve(&config).unwrap().unwrap();
+        let result = reloaded.solve(&config_reloaded).unwrap().unwrap();
+        assert_same_solution(&expected, &result);
+
+        let json = serde_json::to_string(&result).unwrap();
+        assert_same_solution(&result, &serde_json::from_str(&json).unwrap());
+    }
+
+    #[test]
+    fn test_explicit_round_trip() {
+        let json = r#"{"metric":"Explicit","kinds":["Target","Target","Target"],"coords":[],
+            "meta":[1,2,3],"computed":true,"costs":[0,1.5,2.5,1.5,0,3.5,2.5,3.5,0]}"#;
+        let store: DataStore<usize> = serde_json::from_str(json).unwrap();
+        assert_eq!(3.5, store.cost(&1, &2));
+
+        let reloaded: DataStore<usize> =
+            serde_json::from_str(&serde_json::to_string(&store).unwrap()).unwrap();
+        for &(a, b) in &[(0, 1), (0, 2), (1, 2)] {
+            assert_eq!(store.cost(&a, &b), reloaded.cost(&a, &b));
+        }
+
+        // The matrix must cover all pairs of nodes.
+        let json = json.replace(",3.5,0]", "]");
+        assert!(serde_json::from_str::<DataStore<usize>>(&json).is_err());
+    }
+
+    #[test]
+    fn test_custom_metric_rejected() {
+        let json = r#"{"metric":"Custom","kinds":[],"coords":[],"meta":[],"computed":false}"#;
+        let err = serde_json::from_str::<DataStore<usize>>(json).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("custom metrics cannot be deserialized"));
+    }
+
+    #[test]
+    fn test_pool_invariants() {
+        use crate::SolutionPool;
+
+        let parse = |json: &str| serde_json::from_str::<SolutionPool>(json);
+        let order = |order: &str, cost: f64| format!(r#"{{"order":{},"cost":{}}}"#, order, cost);
+        let pool = |capacity: usize, min_distance: usize, orders: &[String]| {
+            format!(
+                r#"{{"capacity":{},"min_distance":{},"orders":[{}]}}"#,
+                capacity,
+                min_distance,
+                orders.join(",")
+            )
+        };
+
+        let (a, b) = (order("[0,1,2,3,4]", 10.), order("[0,2,1,3,4]", 12.));
+        let result = parse(&pool(2, 0, &[a.clone(), b.clone()])).unwrap();
+        assert_eq!(1, result.min_distance());
+        assert_eq!(2, result.len());
+
+        let round_trip = parse(&serde_json::to_string(&result).unwrap()).unwrap();
+        assert_eq!(result.len(), round_trip.len());
+        for (expected, order) in result.iter().zip(round_trip.iter()) {
+            assert_same_solution(expected, order);
+        }
+
+        for (json, message) in [
+            (pool(1, 1, &[a.clone(), b.clone()]), "more tour orders"),
+            (pool(2, 1, &[b.clone(), a.clone()]), "not sorted by cost"),
+            (pool(2, 5, &[a.clone(), b]), "too similar"),
+            (pool(2, 1, &[a, order("[]", 11.)]), "empty tour order"),
+        ] {
+            assert!(parse(&json).unwrap_err().to_string().contains(message));
+        }
+    }
+
+    #[test]
+    fn test_bincode_round_trip() {
+        let mut model = create_model();
+        model.complete();
+        let config = RunConfigBuilder::new().cands(4).build();
+
+        let bytes = bincode::serialize(&model).unwrap();
+        let mut reloaded: Model<String> = bincode::deserialize(&bytes).unwrap();
+
+        let expected = model.solve(&config).unwrap().unwrap();
+        let result = reloaded.solve(&config).unwrap().unwrap();
+        assert_same_solution(&expected, &result);
+    }
+}
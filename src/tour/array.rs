@@ -0,0 +1,369 @@
+use crate::{
+    data::{DataStore, GetIndex, NodeStore},
+    Scalar,
+};
+
+use super::{
+    between,
+    node::NodeArena,
+    segment::{self, Splice},
+    validate::{debug_check_distance, flip_delta, validate_cycle},
+    InvariantError, NodeRel, Tour, TourIter, TourNode, TourOrder, UpdateTourError,
+};
+
+/// A tour which keeps its nodes in an array ordered by their positions in the tour.
+///
+/// Each node stores its position in the array, so that `successor`, `predecessor` and `between`
+/// take O(1) time. A flip reverses the shorter of the two affected paths, which takes O(N) time
+/// in the worst case. The representation is therefore best suited for small instances.
+#[derive(Clone, Debug)]
+pub struct Array {
+    store: NodeStore,
+    nodes: NodeArena,
+    tour: Vec<TourNode>,
+    total_dist: Scalar,
+    rev: bool,
+}
+
+impl Array {
+    pub fn new<M>(store: &DataStore<M>) -> Self {
+        let n_nodes = store.len();
+
+        let mut result = Self {
+            store: store.store(),
+            nodes: NodeArena::new(n_nodes),
+            tour: (0..n_nodes).map(TourNode::new).collect(),
+            total_dist: 0.,
+            rev: false,
+        };
+
+        result.apply(&TourOrder::with_nat_ord(n_nodes)).unwrap();
+
+        result
+    }
+
+    /// Returns the position of a node in the underlying array.
+    #[inline]
+    fn pos(&self, node: &TourNode) -> usize {
+        self.nodes[node.id].rank as usize
+    }
+
+    /// Returns the node which is right after (```forward```) or right before the given position
+    /// in the underlying array.
+    #[inline]
+    fn adjacent(&self, pos: usize, forward: bool) -> Option<TourNode> {
+        let len = self.tour.len();
+        if len == 0 {
+            return None;
+        }
+
+        if forward {
+            self.tour.get((pos + 1) % len).copied()
+        } else {
+            self.tour.get((pos + len - 1) % len).copied()
+        }
+    }
+
+    /// Reverses the nodes in the cyclic range of positions from `from` to `to`, inclusively.
+    fn reverse(&mut self, from: usize, to: usize) {
+        let len = self.tour.len();
+        let n_swaps = ((to + len - from) % len).div_ceil(2);
+
+        for ii in 0..n_swaps {
+            let (p1, p2) = ((from + ii) % len, (to + len - ii) % len);
+            self.tour.swap(p1, p2);
+            self.set_pos(p1);
+            self.set_pos(p2);
+        }
+    }
+
+    #[inline]
+    fn set_pos(&mut self, pos: usize) {
+        let id = self.tour[pos].id;
+        self.nodes[id].rank = pos as i32;
+    }
+}
+
+impl Tour for Array {
+    fn apply(&mut self, tour: &TourOrder) -> Result<(), UpdateTourError> {
+        let order = tour.order();
+        let len = self.nodes.len();
+        tour.validate(len)?;
+
+        self.rev = false;
+        self.total_dist = 0.;
+
+        for (pos, &idx) in order.iter().enumerate() {
+            self.tour[pos] = TourNode::new(idx);
+            self.set_pos(pos);
+            self.total_dist += self.store.cost(&idx, &order[(pos + 1) % len]);
+        }
+
+        Ok(())
+    }
+
+    #[inline]
+    fn between(&self, from: &TourNode, mid: &TourNode, to: &TourNode) -> bool {
+        let (f, m, t) = (self.pos(from), self.pos(mid), self.pos(to));
+        if self.rev {
+            between(t, m, f)
+        } else {
+            between(f, m, t)
+        }
+    }
+
+    #[inline]
+    fn between_at(&self, from_index: usize, mid_index: usize, to_index: usize) -> bool {
+        match (
+            self.get(from_index),
+            self.get(mid_index),
+            self.get(to_index),
+        ) {
+            (Some(from), Some(mid), Some(to)) => self.between(&from, &mid, &to),
+            _ => false,
+        }
+    }
+
+    #[inline]
+    fn distance_at<I>(&self, a: &I, b: &I) -> Scalar
+    where
+        I: GetIndex + PartialEq + Eq,
+    {
+        self.store.cost(a, b)
+    }
+
+    #[inline]
+    fn coords(&self, node: &TourNode) -> &[Scalar] {
+        self.store.coords(&node.index())
+    }
+
+    #[inline]
+    fn flip_at(&mut self, from_a: usize, to_a: usize, from_b: usize, to_b: usize) {
+        if let (Some(fa), Some(ta), Some(fb), Some(tb)) = (
+            self.get(from_a),
+            self.get(to_a),
+            self.get(from_b),
+            self.get(to_b),
+        ) {
+            self.flip(&fa, &ta, &fb, &tb);
+        }
+    }
+
+    fn flip(&mut self, from_a: &TourNode, to_a: &TourNode, from_b: &TourNode, to_b: &TourNode) {
+        let len = self.tour.len();
+        let (fa, ta, fb, tb) = (
+            self.pos(from_a),
+            self.pos(to_a),
+            self.pos(from_b),
+            self.pos(to_b),
+        );
+
+        // The forward paths (to_a, from_b) and (to_b, from_a) as ranges in the underlying array.
+        let (p1, p2) = if self.rev {
+            ((fb, ta), (fa, tb))
+        } else {
+            ((ta, fb), (tb, fa))
+        };
+
+        // Reversing either path results in the same tour, thus the shorter one is chosen.
+        if (p1.1 + len - p1.0) % len <= (p2.1 + len - p2.0) % len {
+            self.reverse(p1.0, p1.1);
+        } else {
+            self.reverse(p2.0, p2.1);
+        }
+
+        self.total_dist += flip_delta(self, from_a, to_a, from_b, to_b);
+
+        debug_check_distance(self);
+
+        #[cfg(feature = "validate")]
+        super::validate::check_flip(self, from_a, to_a, from_b, to_b);
+    }
+
+    /// Rotates the nodes between the path and the target edge in the underlying array, so that
+    /// the path lands next to `to`, and reverses the path if needed. Only the shorter stretch of
+    /// nodes on either side of the path is touched, which takes O(K + M) time for a path of K
+    /// nodes and M nodes in that stretch.
+    fn move_segment(
+        &mut self,
+        first: &TourNode,
+        last: &TourNode,
+        to: &TourNode,
+        reverse: bool,
+    ) -> Scalar {
+        let delta = match segment::prepare(self, first, last, to, reverse) {
+            Splice::Done(delta) => return delta,
+            Splice::Pending(delta) => delta,
+        };
+
+        // The path as a range of positions, and the position after which it is inserted.
+        let len = self.tour.len();
+        let (lo, hi, at) = if self.rev {
+            (
+                self.pos(last),
+                self.pos(first),
+                (self.pos(to) + len - 1) % len,
+            )
+        } else {
+            (self.pos(first), self.pos(last), self.pos(to))
+        };
+
+        // The path keeps its order in the array unless it is reversed. Both stretches of nodes
+        // are non-empty since `to` neither lies on the path nor precedes it.
+        let n_after = (at + len - hi) % len;
+        let n_before = (lo + len - at - 1) % len;
+        if n_after <= n_before {
+            // [lo..hi] [hi + 1..at] becomes [hi + 1..at] [lo..hi].
+            if !reverse {
+                self.reverse(lo, hi);
+            }
+            self.reverse((hi + 1) % len, at);
+            self.reverse(lo, at);
+        } else {
+            // [at + 1..lo - 1] [lo..hi] becomes [lo..hi] [at + 1..lo - 1].
+            self.reverse((at + 1) % len, (lo + len - 1) % len);
+            if !reverse {
+                self.reverse(lo, hi);
+            }
+            self.reverse((at + 1) % len, hi);
+        }
+
+        self.total_dist += delta;
+
+        debug_check_distance(self);
+
+        #[cfg(feature = "validate")]
+        super::validate::check_move(self, first, last, to);
+
+        delta
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<TourNode> {
+        self.nodes.get(index)
+    }
+
+    #[inline]
+    fn relation(&self, base: &TourNode, targ: &TourNode) -> NodeRel {
+        if self.successor(base) == Some(*targ) {
+            NodeRel::Predecessor
+        } else if self.predecessor(base) == Some(*targ) {
+            NodeRel::Successor
+        } else {
+            NodeRel::None
+        }
+    }
+
+    #[inline]
+    fn successor(&self, kin: &TourNode) -> Option<TourNode> {
+        if !self.nodes.contains(kin) {
+            return None;
+        }
+        self.adjacent(self.pos(kin), !self.rev)
+    }
+
+    #[inline]
+    fn successor_at(&self, kin_index: usize) -> Option<TourNode> {
+        match self.nodes.get(kin_index) {
+            Some(kin) => self.successor(&kin),
+            None => None,
+        }
+    }
+
+    #[inline]
+    fn predecessor(&self, kin: &TourNode) -> Option<TourNode> {
+        if !self.nodes.contains(kin) {
+            return None;
+        }
+        self.adjacent(self.pos(kin), self.rev)
+    }
+
+    #[inline]
+    fn predecessor_at(&self, kin_index: usize) -> Option<TourNode> {
+        match self.nodes.get(kin_index) {
+            Some(kin) => self.predecessor(&kin),
+            None => None,
+        }
+    }
+
+    #[inline]
+    fn rev(&mut self) {
+        self.rev ^= true;
+    }
+
+    fn tour_order(&self) -> TourOrder {
+        let len = self.tour.len();
+        match self.nodes.get(0) {
+            Some(first) => {
+                let start = self.pos(&first);
+                let order: Vec<usize> = (0..len)
+                    .map(|ii| {
+                        let pos = if self.rev {
+                            (start + len - ii) % len
+                        } else {
+                            (start + ii) % len
+                        };
+                        self.tour[pos].index()
+                    })
+                    .collect();
+
+                let mut result = TourOrder::with_ord(order);
+                result.set_cost(self.measure(&result));
+                result
+            }
+            None => TourOrder::default(),
+        }
+    }
+
+    fn measure(&self, to: &TourOrder) -> Scalar {
+        if !to.is_empty() && self.len() == to.len() {
+            let v = to.order();
+            let mut cost = self.distance_at(v.first().unwrap(), v.last().unwrap());
+            for pair in v.windows(2) {
+                cost += self.distance_at(&pair[0], &pair[1]);
+            }
+            cost
+        } else {
+            0.
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    #[inline]
+    fn total_distance(&self) -> Scalar {
+        self.total_dist
+    }
+
+    fn validate(&self) -> Result<(), InvariantError> {
+        if self.tour.len() != self.nodes.len() {
+            return Err(InvariantError::NotACycle {
+                expected: self.nodes.len(),
+                received: self.tour.len(),
+            });
+        }
+
+        for (pos, node) in self.tour.iter().enumerate() {
+            if !self.nodes.contains(node) {
+                return Err(InvariantError::BrokenLink { node: pos });
+            }
+            if self.pos(node) != pos {
+                return Err(InvariantError::BrokenPosition { node: node.index() });
+            }
+        }
+
+        validate_cycle(self)
+    }
+
+    fn itr(&self) -> TourIter {
+        self.nodes.itr()
+    }
+}
@@ -1,12 +1,48 @@
+use crate::Scalar;
+
 #[derive(Debug)]
 pub enum UpdateTourError {
     BrokenTour,
 
-    TourLenMismatched { expected: usize, received: usize },
+    TourLenMismatched {
+        expected: usize,
+        received: usize,
+    },
 
-    InvalidTourOrder,
+    /// The value at the position in a tour order is out of range or appears more than once.
+    InvalidTourOrder {
+        position: usize,
+        value: usize,
+    },
 
     NodeNotFound,
 
     SearchFailed,
+
+    /// The search does not support the requested k-opt move yet.
+    UnsupportedMove,
+}
+
+/// A structural invariant of a tour which is violated, as reported by
+/// [`Tour::validate`](super::Tour::validate).
+#[derive(Clone, Debug, PartialEq)]
+pub enum InvariantError {
+    /// Following successors from the first node does not visit every node exactly once.
+    NotACycle { expected: usize, received: usize },
+
+    /// The successor and predecessor links of the node are not mutually consistent.
+    BrokenLink { node: usize },
+
+    /// `between` disagrees with the order of the node and its neighbours.
+    BrokenOrder { node: usize },
+
+    /// The position or rank of the node in the underlying structure is inconsistent.
+    BrokenPosition { node: usize },
+
+    /// The links, rank, size or flags of a segment or subtree are inconsistent. For splay trees,
+    /// the index of the subtree's root node is reported.
+    BrokenSegment { segment: usize },
+
+    /// The stored total distance differs from the sum of the tour's edge lengths.
+    DistanceMismatched { expected: Scalar, received: Scalar },
 }
@@ -0,0 +1,308 @@
+use crate::{data::GetIndex, Scalar};
+
+use super::{InvariantError, NodeRel, Tour, TourIter, TourNode, TourOrder, UpdateTourError};
+
+/// A position in the journal of a [`Journal`], to which the tour can be rolled back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Checkpoint {
+    /// The number of commits before the checkpoint was taken.
+    generation: usize,
+    /// The number of entries recorded when the checkpoint was taken.
+    position: usize,
+}
+
+/// An operation recorded in the journal.
+#[derive(Clone, Copy, Debug)]
+enum Entry {
+    Flip(TourNode, TourNode, TourNode, TourNode),
+    /// A path from its first to its last node, moved away from its former neighbours.
+    Move(TourNode, TourNode, TourNode, TourNode),
+    Rev,
+}
+
+/// A tour that records its flips and reversals, so that tentative moves can be reverted exactly.
+///
+/// Recording starts with the first [`checkpoint`](Journal::checkpoint) and stops once the
+/// journal is committed. Rolling back undoes the recorded operations in reverse order, each of
+/// which takes the time of a single flip of the wrapped tour, instead of applying a stored
+/// [`TourOrder`] in O(N) time.
+#[derive(Clone, Debug)]
+pub struct Journal<T> {
+    tour: T,
+    entries: Vec<Entry>,
+    recording: bool,
+    generation: usize,
+}
+
+impl<T> Journal<T>
+where
+    T: Tour,
+{
+    pub fn new(tour: T) -> Self {
+        Self {
+            tour,
+            entries: Vec::new(),
+            recording: false,
+            generation: 0,
+        }
+    }
+
+    /// Returns a reference to the wrapped tour.
+    pub fn inner(&self) -> &T {
+        &self.tour
+    }
+
+    /// Consumes the journal and returns the wrapped tour in its current state.
+    pub fn into_inner(self) -> T {
+        self.tour
+    }
+
+    /// Returns the number of operations recorded since the last commit.
+    pub fn depth(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns ```true``` if operations on the tour are currently recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Marks the current state of the tour and starts recording if not already done.
+    ///
+    /// Checkpoints can be nested: rolling back to an earlier checkpoint also reverts all
+    /// operations made after any later checkpoint.
+    pub fn checkpoint(&mut self) -> Checkpoint {
+        self.recording = true;
+        Checkpoint {
+            generation: self.generation,
+            position: self.entries.len(),
+        }
+    }
+
+    /// Reverts all operations recorded after the given checkpoint and returns the number of
+    /// reverted operations.
+    ///
+    /// Checkpoints taken after `checkpoint` are invalidated. A checkpoint taken before the last
+    /// commit refers to a state which is no longer recorded, thus nothing is reverted.
+    pub fn rollback(&mut self, checkpoint: Checkpoint) -> usize {
+        if checkpoint.generation != self.generation {
+            return 0;
+        }
+
+        let mut count = 0;
+        while self.entries.len() > checkpoint.position {
+            match self.entries.pop() {
+                Some(Entry::Flip(a, b, c, d)) => {
+                    // The flip replaced (a, b) and (c, d) by (a, c) and (b, d), whose directions
+                    // depend on which path the tour reversed.
+                    if self.tour.successor(&a) == Some(c) {
+                        self.tour.flip(&a, &c, &b, &d);
+                    } else {
+                        self.tour.flip(&c, &a, &d, &b);
+                    }
+
+                    // Undoing may reverse the complementary path, which restores the edges but
+                    // not the direction of the tour.
+                    if self.tour.successor(&a) != Some(b) {
+                        self.tour.rev();
+                    }
+                }
+                Some(Entry::Move(first, last, p, n)) => {
+                    self.undo_move(&first, &last, &p, &n);
+                }
+                Some(Entry::Rev) => self.tour.rev(),
+                None => break,
+            }
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Accepts all recorded operations, clears the journal and stops recording.
+    ///
+    /// All previously taken checkpoints are invalidated.
+    pub fn commit(&mut self) {
+        self.entries.clear();
+        self.recording = false;
+        self.generation += 1;
+    }
+
+    /// Moves the path from `first` to `last` back between its former neighbours `p` and `n`,
+    /// and restores the direction of the tour.
+    fn undo_move(&mut self, first: &TourNode, last: &TourNode, p: &TourNode, n: &TourNode) {
+        // The path in its current direction, and the node which precedes it.
+        let (head, tail) = if self.tour.between(first, last, p) {
+            (*first, *last)
+        } else {
+            (*last, *first)
+        };
+        let pred = self.tour.predecessor(&head).unwrap();
+        let succ = self.tour.successor(&tail).unwrap();
+
+        // The path is moved after whichever of p and n precedes the other once the path is gone,
+        // unless it already lies between them and only has to be reversed.
+        let to = if (pred == *p && succ == *n) || (pred == *n && succ == *p) {
+            pred
+        } else if self.tour.successor(p) == Some(*n) {
+            *p
+        } else {
+            *n
+        };
+        let next = if to == *p { first } else { last };
+        self.tour.move_segment(&head, &tail, &to, head != *next);
+
+        if self.tour.successor(p) != Some(*first) {
+            self.tour.rev();
+        }
+    }
+
+    #[inline]
+    fn record(&mut self, entry: Entry) {
+        if self.recording {
+            self.entries.push(entry);
+        }
+    }
+}
+
+impl<T> Tour for Journal<T>
+where
+    T: Tour,
+{
+    /// Applies the order to the wrapped tour and commits the journal, since the previous state
+    /// cannot be restored by undoing flips.
+    fn apply(&mut self, order: &TourOrder) -> Result<(), UpdateTourError> {
+        self.commit();
+        self.tour.apply(order)
+    }
+
+    #[inline]
+    fn between(&self, from: &TourNode, mid: &TourNode, to: &TourNode) -> bool {
+        self.tour.between(from, mid, to)
+    }
+
+    #[inline]
+    fn between_at(&self, from_index: usize, mid_index: usize, to_index: usize) -> bool {
+        self.tour.between_at(from_index, mid_index, to_index)
+    }
+
+    #[inline]
+    fn distance(&self, a: &TourNode, b: &TourNode) -> Scalar {
+        self.tour.distance(a, b)
+    }
+
+    #[inline]
+    fn distance_at<I>(&self, a: &I, b: &I) -> Scalar
+    where
+        I: GetIndex + PartialEq + Eq,
+    {
+        self.tour.distance_at(a, b)
+    }
+
+    #[inline]
+    fn coords(&self, node: &TourNode) -> &[Scalar] {
+        self.tour.coords(node)
+    }
+
+    #[inline]
+    fn flip_at(&mut self, from_a: usize, to_a: usize, from_b: usize, to_b: usize) {
+        if let (Some(fa), Some(ta), Some(fb), Some(tb)) = (
+            self.get(from_a),
+            self.get(to_a),
+            self.get(from_b),
+            self.get(to_b),
+        ) {
+            self.flip(&fa, &ta, &fb, &tb);
+        }
+    }
+
+    fn flip(&mut self, from_a: &TourNode, to_a: &TourNode, from_b: &TourNode, to_b: &TourNode) {
+        self.tour.flip(from_a, to_a, from_b, to_b);
+        self.record(Entry::Flip(*from_a, *to_a, *from_b, *to_b));
+    }
+
+    fn move_segment(
+        &mut self,
+        first: &TourNode,
+        last: &TourNode,
+        to: &TourNode,
+        reverse: bool,
+    ) -> Scalar {
+        let (p, n) = match (self.tour.predecessor(first), self.tour.successor(last)) {
+            (Some(p), Some(n)) => (p, n),
+            _ => panic!("Node not found while moving segment."),
+        };
+
+        let delta = self.tour.move_segment(first, last, to, reverse);
+        if *to != p || reverse {
+            self.record(Entry::Move(*first, *last, p, n));
+        }
+        delta
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<TourNode> {
+        self.tour.get(index)
+    }
+
+    #[inline]
+    fn relation(&self, base: &TourNode, targ: &TourNode) -> NodeRel {
+        self.tour.relation(base, targ)
+    }
+
+    #[inline]
+    fn successor(&self, kin: &TourNode) -> Option<TourNode> {
+        self.tour.successor(kin)
+    }
+
+    #[inline]
+    fn successor_at(&self, kin_index: usize) -> Option<TourNode> {
+        self.tour.successor_at(kin_index)
+    }
+
+    #[inline]
+    fn predecessor(&self, kin: &TourNode) -> Option<TourNode> {
+        self.tour.predecessor(kin)
+    }
+
+    #[inline]
+    fn predecessor_at(&self, kin_index: usize) -> Option<TourNode> {
+        self.tour.predecessor_at(kin_index)
+    }
+
+    fn rev(&mut self) {
+        self.tour.rev();
+        self.record(Entry::Rev);
+    }
+
+    fn tour_order(&self) -> TourOrder {
+        self.tour.tour_order()
+    }
+
+    fn measure(&self, to: &TourOrder) -> Scalar {
+        self.tour.measure(to)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.tour.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.tour.is_empty()
+    }
+
+    #[inline]
+    fn total_distance(&self) -> Scalar {
+        self.tour.total_distance()
+    }
+
+    fn validate(&self) -> Result<(), InvariantError> {
+        self.tour.validate()
+    }
+
+    fn itr(&self) -> TourIter {
+        self.tour.itr()
+    }
+}
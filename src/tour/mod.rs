@@ -1,22 +1,42 @@
+use enum_dispatch::enum_dispatch;
 use getset::{CopyGetters, Getters};
 
-use crate::data::GetIndex;
+use crate::data::{DataStore, GetIndex};
 use crate::Scalar;
 
+mod array;
+pub use array::Array;
+
+mod splay;
+pub use splay::SplayTree;
+
 mod tll;
 pub use tll::TwoLevelList;
 
 mod node;
-pub use node::exclude;
-pub use node::NodeStatus;
 pub use node::TourNode;
 
+mod journal;
+pub use journal::Checkpoint;
+pub use journal::Journal;
+
 mod error;
+pub use error::InvariantError;
 pub use error::UpdateTourError;
 
+mod order;
+
+mod segment;
+
+mod validate;
+
+mod traversal;
+pub use traversal::Traversal;
+pub use traversal::Traverse;
+
 pub mod tests;
 
-// #[enum_dispatch(TourImpltor)]
+#[enum_dispatch(TourImpltor)]
 pub trait Tour {
     /// Rearranges the tour's vertices according to the given order.
     // TODO: should return Result<()>.
@@ -47,6 +67,12 @@ pub trait Tour {
     where
         I: GetIndex + PartialEq + Eq;
 
+    /// Returns the coordinates of a node, which are empty for metrics without coordinates.
+    ///
+    /// # Panics
+    /// Panics if `node` is out of bounds.
+    fn coords(&self, node: &TourNode) -> &[Scalar];
+
     /// Permutate the tour's order by replacing the edges `(from_a, to_a)` and `(from_b, to_b)`
     /// by the new edges `(from_a, from_b)` and `(to_a, to_b)`.
     ///
@@ -64,6 +90,24 @@ pub trait Tour {
 
     fn flip(&mut self, from_a: &TourNode, to_a: &TourNode, from_b: &TourNode, to_b: &TourNode);
 
+    /// Moves the path from `first` to `last` of the forward traversal between the node `to` and
+    /// its successor, and returns the resulting change in the total distance.
+    ///
+    /// The path is connected to `to` by `first` and to the successor of `to` by `last`, or the
+    /// other way around if `reverse` is ```true```. As with flips, the direction of the tour
+    /// afterwards is specified by the concrete implementation. Short paths, such as those of the
+    /// or-opt neighbourhood, are moved at a cost similar to that of a single flip.
+    ///
+    /// # Panics
+    /// Panics if any of the nodes is not in the tour, or if `to` lies on the path.
+    fn move_segment(
+        &mut self,
+        first: &TourNode,
+        last: &TourNode,
+        to: &TourNode,
+        reverse: bool,
+    ) -> Scalar;
+
     /// Returns a reference to a vertex representing a node in this tour.
     ///
     /// If a node is registered in the container of this tour, returns the reference to its
@@ -128,9 +172,6 @@ pub trait Tour {
     /// The function will only calculate the distance and will not cause any structural changes.
     fn measure(&self, to: &TourOrder) -> Scalar;
 
-    /// Resets all the internal states of the tour and its vertices.
-    fn reset(&mut self);
-
     /// Returns the number of vertices in the tour.
     fn len(&self) -> usize;
 
@@ -138,12 +179,77 @@ pub trait Tour {
     fn is_empty(&self) -> bool;
 
     /// Returns the total distance of completely traversing through the tour.
+    ///
+    /// The distance is updated incrementally from the changed edges on every flip, thus the call
+    /// takes O(1) time. Debug builds cross-check it against a full recomputation after each flip.
     fn total_distance(&self) -> Scalar;
 
-    /// Returns the iterator over all nodes stored in a tour.
+    /// Checks all structural invariants of the tour, including the consistency of its total
+    /// distance, and returns the first violation found.
+    ///
+    /// The check takes O(N) time or more. With the cargo feature ```validate```, tours run it
+    /// after every flip and panic with the offending operation.
+    fn validate(&self) -> Result<(), InvariantError>;
+
+    /// Returns the iterator over all nodes stored in a tour, in the order of their indices.
+    ///
+    /// To iterate over nodes in tour order, see [`Traverse`].
     fn itr(&self) -> TourIter;
 }
 
+/// A tour whose backend is chosen at runtime.
+#[enum_dispatch]
+#[derive(Clone, Debug)]
+pub enum TourImpltor {
+    Array,
+    TwoLevelList,
+    SplayTree,
+}
+
+impl TourImpltor {
+    /// Creates a tour with the backend of the given kind.
+    ///
+    /// The group size is only used by [`TwoLevelList`].
+    pub fn new<M>(kind: TourKind, store: &DataStore<M>, groupsize: usize) -> Self {
+        match kind.resolve(store.len()) {
+            TourKind::Array => Array::new(store).into(),
+            TourKind::SplayTree => SplayTree::new(store).into(),
+            TourKind::TwoLevelList | TourKind::Auto => TwoLevelList::new(store, groupsize).into(),
+        }
+    }
+}
+
+/// The backend of a tour.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TourKind {
+    /// Chooses a backend based on the number of nodes. See [`TourKind::resolve`].
+    #[default]
+    Auto,
+    Array,
+    TwoLevelList,
+    SplayTree,
+}
+
+impl TourKind {
+    /// The maximum number of nodes for which [`TourKind::Auto`] chooses [`Array`].
+    pub const ARRAY_MAX_NODES: usize = 1_000;
+
+    /// The maximum number of nodes for which [`TourKind::Auto`] chooses [`TwoLevelList`].
+    /// Larger instances use [`SplayTree`].
+    pub const TLL_MAX_NODES: usize = 100_000;
+
+    /// Returns the concrete backend for a tour with the given number of nodes.
+    pub fn resolve(self, n_nodes: usize) -> Self {
+        match self {
+            Self::Auto if n_nodes <= Self::ARRAY_MAX_NODES => Self::Array,
+            Self::Auto if n_nodes <= Self::TLL_MAX_NODES => Self::TwoLevelList,
+            Self::Auto => Self::SplayTree,
+            kind => kind,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum NodeRel {
     Predecessor,
@@ -151,16 +257,16 @@ pub enum NodeRel {
     None,
 }
 
-pub struct TourIter<'s> {
-    it: std::slice::Iter<'s, TourNode>,
+pub struct TourIter {
+    it: std::ops::Range<u32>,
 }
 
-impl<'s> Iterator for TourIter<'s> {
+impl Iterator for TourIter {
     type Item = TourNode;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        self.it.next().map(|n| TourNode { inner: n.inner })
+        self.it.next().map(|id| TourNode { id })
     }
 
     #[inline]
@@ -169,13 +275,20 @@ impl<'s> Iterator for TourIter<'s> {
     }
 
     #[inline]
-    #[allow(unused_mut)]
     fn last(mut self) -> Option<Self::Item> {
-        // self.it.next_back()
-        todo!()
+        self.next_back()
+    }
+}
+
+impl DoubleEndedIterator for TourIter {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.it.next_back().map(|id| TourNode { id })
     }
 }
 
+impl ExactSizeIterator for TourIter {}
+
 pub trait STree {
     fn build_mst(&mut self);
 
@@ -187,7 +300,8 @@ pub enum HeldKarpBound {
     Optimal,
 }
 
-#[derive(Debug, CopyGetters, Getters)]
+#[derive(Clone, Debug, CopyGetters, Getters)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TourOrder {
     #[getset(get = "pub")]
     order: Vec<usize>,
@@ -245,6 +359,29 @@ impl TourOrder {
     pub fn set_cost(&mut self, cost: Scalar) {
         self.cost = cost;
     }
+
+    /// Checks that the order is a permutation of the node indices from 0 to `n_nodes` - 1.
+    ///
+    /// Returns the first position whose value is either out of range or already appeared at an
+    /// earlier position.
+    pub fn validate(&self, n_nodes: usize) -> Result<(), UpdateTourError> {
+        if self.order.len() != n_nodes {
+            return Err(UpdateTourError::TourLenMismatched {
+                expected: n_nodes,
+                received: self.order.len(),
+            });
+        }
+
+        let mut seen = vec![false; n_nodes];
+        for (position, &value) in self.order.iter().enumerate() {
+            if value >= n_nodes || seen[value] {
+                return Err(UpdateTourError::InvalidTourOrder { position, value });
+            }
+            seen[value] = true;
+        }
+
+        Ok(())
+    }
 }
 
 impl Default for TourOrder {
@@ -1,250 +1,153 @@
-use std::{fmt::Display, ptr::NonNull};
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
 
-use crate::{data::NodeIndex, Scalar};
+use super::TourIter;
 
-#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+/// The index marking the absence of a node or a segment in an arena.
+pub(super) const NIL: u32 = u32::MAX;
+
+/// A handle to a node of a tour.
+///
+/// A handle is the node's index in the arena of its tour, which equals the index of the node in
+/// its data store. Handles are therefore interchangeable between tours over the same store.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct TourNode {
-    pub(super) inner: Option<NonNull<InnerNode>>,
+    pub(super) id: u32,
 }
 
 impl TourNode {
-    pub fn new(node: NodeIndex) -> Self {
-        let inner = InnerNode::new(node);
-        Self {
-            inner: to_nonnull(inner),
-        }
-    }
-
-    /// Updates a node's status.
     #[inline]
-    pub fn set_status(&mut self, status: NodeStatus) {
-        if let Some(inner) = self.inner {
-            unsafe {
-                (*inner.as_ptr()).status = status;
-            }
-        }
+    pub(crate) fn new(index: usize) -> Self {
+        Self { id: index as u32 }
     }
 
-    /// Returns a node's status.
+    /// Returns the index of a node in its data store.
     #[inline]
-    pub fn status(&self) -> NodeStatus {
-        match self.inner {
-            Some(inner) => unsafe { (*inner.as_ref()).status },
-            None => NodeStatus::Undefined,
-        }
+    pub fn index(&self) -> usize {
+        self.id as usize
     }
 
-    /// Checks whether a node is currently in a given status.
+    /// Returns ```true``` if the handle does not refer to any node.
     #[inline]
-    pub fn is_status(&self, status: NodeStatus) -> bool {
-        match self.inner {
-            Some(inner) => unsafe { (*inner.as_ref()).status == status },
-            None => false,
-        }
+    pub fn is_nil(&self) -> bool {
+        self.id == NIL
     }
+}
 
-    #[inline]
-    pub fn index(&self) -> NodeIndex {
-        match self.inner {
-            Some(inner) => unsafe { inner.as_ref().index },
-            None => panic!("Nullpointer"),
-        }
-    }
-
-    #[inline]
-    pub fn set_candidates(&mut self, candidates: Vec<TourNode>) {
-        match self.inner {
-            Some(inner) => unsafe {
-                (*inner.as_ptr()).candidates = candidates;
-            },
-            None => panic!("Nullpointer"),
-        }
-    }
-
-    #[inline]
-    pub fn candidates(&self) -> &Vec<TourNode> {
-        match self.inner {
-            Some(inner) => unsafe { &(*inner.as_ptr()).candidates },
-            None => panic!("Nullpointer"),
-        }
-    }
-
-    #[inline]
-    pub fn is_best_neighbours(&self, other: &TourNode, rank: usize) -> bool {
-        if rank >= 1 {
-            return false;
-        }
-
-        match (self.inner, other.inner) {
-            (Some(inner), Some(other_inner)) => unsafe {
-                match (*inner.as_ref()).best_neighbours[rank].as_ref() {
-                    Some(pair) => pair.0 == other_inner || pair.1 == other_inner,
-                    None => false,
-                }
-            },
-            _ => false,
-        }
-    }
-
-    #[inline]
-    pub fn set_best_neighbours(&self, neighbour1: &TourNode, neighbour2: &TourNode) {
-        if let (Some(inner), Some(inner1), Some(inner2)) =
-            (self.inner, neighbour1.inner, neighbour2.inner)
-        {
-            unsafe {
-                (*inner.as_ptr()).best_neighbours[1] = (*inner.as_ptr()).best_neighbours[0];
-                (*inner.as_ptr()).best_neighbours[0] = Some((inner1, inner2));
-            }
-        }
+impl Default for TourNode {
+    /// Returns a handle which does not refer to any node.
+    fn default() -> Self {
+        Self { id: NIL }
     }
 }
 
 impl Display for TourNode {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self.inner {
-            Some(inner) => write!(f, "TourNode: {}", format!("{:?}", inner)),
-            None => write!(f, "None"),
+        if self.is_nil() {
+            write!(f, "None")
+        } else {
+            write!(f, "TourNode: {}", self.id)
         }
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub(super) struct InnerNode {
-    pub(super) index: NodeIndex,
-    /// Flag indicating whether a node is already visisted/processed by an algorithm.
-    pub(super) status: NodeStatus,
     /// The parent segment in a tour to which a node belongs.
-    pub(super) segment: Option<NonNull<Segment>>,
+    pub(super) segment: u32,
     /// The rank of a node in its parent segment.
     pub(super) rank: i32,
     /// The directly preceding neighbour of a node in a tour.
-    pub(super) predecessor: Option<NonNull<InnerNode>>,
+    pub(super) predecessor: u32,
     /// The directly succeeding neighbour of a node in a tour.
-    pub(super) successor: Option<NonNull<InnerNode>>,
-    /// Number of edges that are incident to the node.
-    pub(super) degree: i32,
-    /// Penalty value of a node in the ascent scheme. Corresponds to pi in LKH report.
-    pub(super) penalty_weight: Scalar,
-    /// Edge with minimum distance that doesn't belong to the MST.
-    // TODO: better name
-    pub(super) mst_final_edge: Option<NonNull<InnerNode>>,
-    /// The parent of a node in a minimum spanning tree.
-    pub(super) mst_parent: Option<NonNull<InnerNode>>,
-    /// Set of candidate nodes.
-    pub(super) candidates: Vec<TourNode>,
-
-    pub(super) best_neighbours: Vec<Option<(NonNull<InnerNode>, NonNull<InnerNode>)>>,
-    /// First original neighbour.
-    pub(super) orgn_nbr_1: Option<NonNull<InnerNode>>,
-    /// Flag to indicate whether an edge with the first original neighbour is excluded.
-    pub(super) orgn_nbr_1_x: bool,
-    /// Second original neighbour.
-    pub(super) orgn_nbr_2: Option<NonNull<InnerNode>>,
-    /// Flag to indicate whether an edge with the second original neighbour is excluded.
-    pub(super) orgn_nbr_2_x: bool,
+    pub(super) successor: u32,
 }
 
-impl InnerNode {
-    pub fn new(node: NodeIndex) -> Self {
+impl Default for InnerNode {
+    fn default() -> Self {
         Self {
-            index: node,
             rank: i32::MAX,
-            status: NodeStatus::Active,
-            segment: None,
-            predecessor: None,
-            successor: None,
-            degree: 0,
-            penalty_weight: 0.,
-            mst_final_edge: None,
-            mst_parent: None,
-            candidates: Vec::with_capacity(0),
-            best_neighbours: vec![None; 2],
-            orgn_nbr_1: None,
-            orgn_nbr_1_x: false,
-            orgn_nbr_2: None,
-            orgn_nbr_2_x: false,
+            segment: NIL,
+            predecessor: NIL,
+            successor: NIL,
         }
     }
 }
 
-impl Display for InnerNode {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", format!("Index: {:?} ", self.index,))
-    }
+/// The nodes of a tour, stored contiguously and addressed by their indices.
+#[derive(Clone, Debug, Default)]
+pub(super) struct NodeArena {
+    nodes: Vec<InnerNode>,
 }
 
-macro_rules! exclude {
-    ($inner:expr, $other:expr) => {
-        if (*$inner.as_ref()).orgn_nbr_1 == $other.inner {
-            (*$inner.as_ptr()).orgn_nbr_1_x = true;
-        } else if (*$inner.as_ref()).orgn_nbr_2 == $other.inner {
-            (*$inner.as_ptr()).orgn_nbr_2_x = true;
+impl NodeArena {
+    pub(super) fn new(n_nodes: usize) -> Self {
+        Self {
+            nodes: vec![InnerNode::default(); n_nodes],
         }
-    };
-}
+    }
+
+    #[inline]
+    pub(super) fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[inline]
+    pub(super) fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
 
-pub fn exclude(one: &mut TourNode, other: &mut TourNode) {
-    if let (Some(inner1), Some(inner2)) = (one.inner, other.inner) {
-        unsafe {
-            exclude!(inner1, other);
-            exclude!(inner2, other);
+    /// Returns ```true``` if the handle refers to a node in the arena.
+    #[inline]
+    pub(super) fn contains(&self, node: &TourNode) -> bool {
+        node.index() < self.nodes.len()
+    }
+
+    /// Returns the handle to the node at the given index.
+    #[inline]
+    pub(super) fn get(&self, index: usize) -> Option<TourNode> {
+        if index < self.nodes.len() {
+            Some(TourNode::new(index))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    pub(super) fn itr(&self) -> TourIter {
+        TourIter {
+            it: 0..self.nodes.len() as u32,
         }
     }
 }
 
-#[derive(Debug)]
+impl Index<u32> for NodeArena {
+    type Output = InnerNode;
+
+    #[inline]
+    fn index(&self, index: u32) -> &Self::Output {
+        &self.nodes[index as usize]
+    }
+}
+
+impl IndexMut<u32> for NodeArena {
+    #[inline]
+    fn index_mut(&mut self, index: u32) -> &mut Self::Output {
+        &mut self.nodes[index as usize]
+    }
+}
+
+#[derive(Clone, Debug)]
 pub struct Segment {
     pub(super) rank: usize,
     pub(super) max_len: usize,
     pub(super) reverse: bool,
-    pub(super) first: Option<NonNull<InnerNode>>,
-    pub(super) last: Option<NonNull<InnerNode>>,
-    pub(super) next: Option<NonNull<Segment>>,
-    pub(super) prev: Option<NonNull<Segment>>,
-}
-
-macro_rules! move_node {
-    ($s:expr, $target:ident, $kin1:ident, $kin2:ident, $reverse:expr, $head:ident, $tail:ident, $el_cnt:ident, $op:tt) => {
-        match $s.$target {
-            Some(target_node) => {
-                let mut rank = 1;
-                let target_rank = (*target_node.as_ptr()).rank;
-                let seg = (*target_node.as_ptr()).segment;
-
-                if $reverse {
-                    let mut opt = $head;
-                    while rank <= $el_cnt {
-                        match opt {
-                            Some(node) => {
-                                opt = (*node.as_ptr()).$kin2;
-                                (*node.as_ptr()).rank = target_rank $op rank;
-                                (*node.as_ptr()).segment = seg;
-                                std::mem::swap(&mut (*node.as_ptr()).successor, &mut (*node.as_ptr()).predecessor)
-                            }
-                            None => panic!("Nullpointer"),
-                        }
-                        rank += 1;
-                    }
-                    $s.$target = $tail;
-                } else {
-                    let mut opt = $tail;
-                    while rank <= $el_cnt {
-                        match opt {
-                            Some(node) => {
-                                opt = (*node.as_ptr()).$kin1;
-                                (*node.as_ptr()).rank = target_rank $op rank;
-                                (*node.as_ptr()).segment = seg;
-                            }
-                            None => panic!("Nullpointer"),
-                        }
-                        rank += 1;
-                    }
-                    $s.$target = $head;
-                }
-            }
-            None => panic!("First not found"),
-        }
-    };
+    pub(super) first: u32,
+    pub(super) last: u32,
+    pub(super) next: u32,
+    pub(super) prev: u32,
 }
 
 impl Segment {
@@ -253,364 +156,485 @@ impl Segment {
             rank,
             max_len,
             reverse: false,
-            first: None,
-            last: None,
-            next: None,
-            prev: None,
+            first: NIL,
+            last: NIL,
+            next: NIL,
+            prev: NIL,
         }
     }
 
     #[inline]
     pub fn reset(&mut self) {
         self.reverse = false;
-        self.first = None;
-        self.last = None;
-        self.next = None;
-        self.prev = None;
+        self.first = NIL;
+        self.last = NIL;
+        self.next = NIL;
+        self.prev = NIL;
         self.rank = 0;
     }
+}
 
-    #[inline]
-    pub unsafe fn reverse(&mut self) {
-        // TODO: better panic message.
-        match (&self.first, &self.last) {
-            (Some(first), Some(last)) => {
-                match (&(*first.as_ptr()).predecessor, &(*last.as_ptr()).successor) {
-                    (Some(p), Some(s)) => {
-                        if (*p.as_ptr()).predecessor == self.first {
-                            (*p.as_ptr()).predecessor = self.last;
-                        } else {
-                            (*p.as_ptr()).successor = self.last;
-                        }
-
-                        if (*s.as_ptr()).predecessor == self.last {
-                            (*s.as_ptr()).predecessor = self.first;
-                        } else {
-                            (*s.as_ptr()).successor = self.first;
-                        }
-                    }
-                    _ => panic!("Empty predecessor or successor in node."),
-                }
-                std::mem::swap(
-                    &mut (*first.as_ptr()).predecessor,
-                    &mut (*last.as_ptr()).successor,
-                );
-            }
-            _ => panic!("Empty first or last pointers in segment."),
+/// Returns the neighbour of a node which is next to it in the internal order of its segment
+/// (```succ```) or right before it.
+#[inline]
+fn kin(node: &InnerNode, succ: bool) -> u32 {
+    if succ {
+        node.successor
+    } else {
+        node.predecessor
+    }
+}
+
+/// Replaces the link of `target` to `cond_kin` by a link to `new_kin`.
+#[inline]
+fn change_kin(nodes: &mut NodeArena, target: u32, cond_kin: u32, new_kin: u32) {
+    if target == NIL {
+        panic!("No predecessor when attempting to reverse segment.");
+    }
+
+    if nodes[target].predecessor == cond_kin {
+        nodes[target].predecessor = new_kin;
+    } else {
+        nodes[target].successor = new_kin;
+    }
+}
+
+/// Reverses a segment entirely by toggling its reverse flag and relinking its end nodes.
+pub(super) fn reverse_seg(nodes: &mut NodeArena, segs: &mut [Segment], seg: u32) {
+    let (first, last) = (segs[seg as usize].first, segs[seg as usize].last);
+    if first == NIL || last == NIL {
+        panic!("Empty first or last nodes in segment.");
+    }
+
+    let (p, s) = (nodes[first].predecessor, nodes[last].successor);
+    if p == NIL || s == NIL {
+        panic!("Empty predecessor or successor in node.");
+    }
+
+    if nodes[p].predecessor == first {
+        nodes[p].predecessor = last;
+    } else {
+        nodes[p].successor = last;
+    }
+
+    if nodes[s].predecessor == last {
+        nodes[s].predecessor = first;
+    } else {
+        nodes[s].successor = first;
+    }
+
+    let tmp = nodes[first].predecessor;
+    nodes[first].predecessor = nodes[last].successor;
+    nodes[last].successor = tmp;
+
+    segs[seg as usize].reverse ^= true;
+}
+
+/// Moves `el_cnt` nodes from the path between `head` and `tail` into the segment `seg`, next to
+/// its first (```at_first```) or last node.
+///
+/// The nodes are walked from `head` along `kin2` if `reverse` is set, in which case their links
+/// are swapped, and from `tail` along `kin1` otherwise. `kin1_succ` tells whether `kin1` is the
+/// successor link. The ranks of the moved nodes continue from the rank of the target node in
+/// the direction of `sign`.
+#[allow(clippy::too_many_arguments)]
+fn move_nodes(
+    nodes: &mut NodeArena,
+    segs: &mut [Segment],
+    seg: u32,
+    at_first: bool,
+    kin1_succ: bool,
+    reverse: bool,
+    head: u32,
+    tail: u32,
+    el_cnt: i32,
+    sign: i32,
+) {
+    let target = if at_first {
+        segs[seg as usize].first
+    } else {
+        segs[seg as usize].last
+    };
+
+    if target == NIL {
+        panic!("First not found");
+    }
+
+    let target_rank = nodes[target].rank;
+    let target_seg = nodes[target].segment;
+
+    let mut rank = 1;
+    let (mut opt, succ) = if reverse {
+        (head, !kin1_succ)
+    } else {
+        (tail, kin1_succ)
+    };
+
+    while rank <= el_cnt {
+        if opt == NIL {
+            panic!("Missing node while moving nodes between segments.");
         }
-        self.reverse ^= true;
-    }
-
-    pub(super) unsafe fn split(&mut self, node: &NonNull<InnerNode>) {
-        match (self.first, self.last) {
-            (Some(first), Some(last)) => {
-                let (f1, f2) = if self.reverse { (1, 0) } else { (0, 1) };
-
-                let d1 = (*node.as_ptr()).rank - (*first.as_ptr()).rank + f1;
-                let d2 = (*last.as_ptr()).rank - (*node.as_ptr()).rank + f2;
-
-                if d1 <= d2 {
-                    if self.reverse {
-                        let tmp_ptr = (*node.as_ptr()).successor;
-                        match self.next {
-                            Some(next) => {
-                                (*next.as_ptr()).move_front(
-                                    self.first,
-                                    Some(*node),
-                                    d1,
-                                    self.reverse,
-                                );
-                            }
-                            None => panic!("No next"),
-                        }
-                        self.first = tmp_ptr;
-                    } else {
-                        match self.prev {
-                            Some(prev) => {
-                                (*prev.as_ptr()).move_back(
-                                    self.first,
-                                    (*node.as_ptr()).predecessor,
-                                    d1,
-                                    self.reverse,
-                                );
-                            }
-                            None => panic!("No prev"),
-                        }
-                        self.first = Some(*node);
-                    }
-                } else if self.reverse {
-                    match self.prev {
-                        Some(prev) => {
-                            (*prev.as_ptr()).move_back(
-                                (*node.as_ptr()).successor,
-                                self.last,
-                                d2,
-                                self.reverse,
-                            );
-                        }
-                        None => panic!("No prev"),
-                    }
-                    self.last = Some(*node);
-                } else {
-                    let tmp_ptr = (*node.as_ptr()).predecessor;
-                    match self.next {
-                        Some(next) => {
-                            (*next.as_ptr()).move_front(Some(*node), self.last, d2, self.reverse);
-                        }
-                        None => panic!("No next"),
-                    }
-                    self.last = tmp_ptr;
-                }
-            }
-            _ => panic!("Missing first/last"),
+
+        let node = &mut nodes[opt];
+        opt = kin(node, succ);
+        node.rank = target_rank + sign * rank;
+        node.segment = target_seg;
+        if reverse {
+            std::mem::swap(&mut node.successor, &mut node.predecessor);
         }
+        rank += 1;
     }
 
-    pub(super) unsafe fn move_back(
-        &mut self,
-        head: Option<NonNull<InnerNode>>,
-        tail: Option<NonNull<InnerNode>>,
-        el_cnt: i32,
-        reverse: bool,
-    ) {
-        if self.reverse {
-            move_node!(self, first, predecessor, successor, !reverse, head, tail, el_cnt, -);
-        } else {
-            move_node!(self, last, successor, predecessor, reverse, tail, head, el_cnt, +);
-        }
+    let new_target = if reverse { tail } else { head };
+    if at_first {
+        segs[seg as usize].first = new_target;
+    } else {
+        segs[seg as usize].last = new_target;
     }
+}
 
-    pub(super) unsafe fn move_front(
-        &mut self,
-        head: Option<NonNull<InnerNode>>,
-        tail: Option<NonNull<InnerNode>>,
-        el_cnt: i32,
-        reverse: bool,
-    ) {
-        if self.reverse {
-            move_node!(self, last, successor, predecessor, !reverse, tail, head, el_cnt, +);
-        } else {
-            move_node!(self, first, predecessor, successor, reverse, head, tail, el_cnt, -);
-        }
+/// Moves the nodes of the path from `head` to `tail` to the back of the segment `seg`.
+fn move_back(
+    nodes: &mut NodeArena,
+    segs: &mut [Segment],
+    seg: u32,
+    head: u32,
+    tail: u32,
+    el_cnt: i32,
+    reverse: bool,
+) {
+    if segs[seg as usize].reverse {
+        move_nodes(
+            nodes, segs, seg, true, false, !reverse, head, tail, el_cnt, -1,
+        );
+    } else {
+        move_nodes(
+            nodes, segs, seg, false, true, reverse, tail, head, el_cnt, 1,
+        );
     }
 }
 
-#[inline]
-pub fn to_nonnull<T>(x: T) -> Option<NonNull<T>> {
-    let boxed = Box::new(x);
-    Some(Box::leak(boxed).into())
+/// Moves the nodes of the path from `head` to `tail` to the front of the segment `seg`.
+fn move_front(
+    nodes: &mut NodeArena,
+    segs: &mut [Segment],
+    seg: u32,
+    head: u32,
+    tail: u32,
+    el_cnt: i32,
+    reverse: bool,
+) {
+    if segs[seg as usize].reverse {
+        move_nodes(
+            nodes, segs, seg, false, true, !reverse, tail, head, el_cnt, 1,
+        );
+    } else {
+        move_nodes(
+            nodes, segs, seg, true, false, reverse, head, tail, el_cnt, -1,
+        );
+    }
 }
 
-macro_rules! change_kin {
-    ($target:ident, $cond_kin:ident, $new_kin:ident) => {
-        match $target {
-            Some(node) => {
-                if (*node.as_ptr()).predecessor == Some(*$cond_kin) {
-                    (*node.as_ptr()).predecessor = Some(*$new_kin);
-                } else {
-                    (*node.as_ptr()).successor = Some(*$new_kin);
-                }
-            }
-            None => panic!("No predecessor when attempting to reverse segment."),
+/// Splits the segment `seg` at `node` by moving the shorter part of the segment into the
+/// neighbouring segment.
+pub(super) fn split(nodes: &mut NodeArena, segs: &mut [Segment], seg: u32, node: u32) {
+    let s = &segs[seg as usize];
+    let (first, last, reverse, next, prev) = (s.first, s.last, s.reverse, s.next, s.prev);
+    if first == NIL || last == NIL {
+        panic!("Missing first/last");
+    }
+
+    let (f1, f2) = if reverse { (1, 0) } else { (0, 1) };
+
+    let d1 = nodes[node].rank - nodes[first].rank + f1;
+    let d2 = nodes[last].rank - nodes[node].rank + f2;
+
+    if d1 <= d2 {
+        if reverse {
+            let tmp = nodes[node].successor;
+            move_front(nodes, segs, next, first, node, d1, reverse);
+            segs[seg as usize].first = tmp;
+        } else {
+            let tail = nodes[node].predecessor;
+            move_back(nodes, segs, prev, first, tail, d1, reverse);
+            segs[seg as usize].first = node;
         }
-    };
+    } else if reverse {
+        let head = nodes[node].successor;
+        move_back(nodes, segs, prev, head, last, d2, reverse);
+        segs[seg as usize].last = node;
+    } else {
+        let tmp = nodes[node].predecessor;
+        move_front(nodes, segs, next, node, last, d2, reverse);
+        segs[seg as usize].last = tmp;
+    }
 }
 
 /// Reverse a segment internally.
 // TODO: better panic msg.
-pub(super) unsafe fn reverse_int_seg(
-    seg: &NonNull<Segment>,
-    a: &NonNull<InnerNode>,
-    b: &NonNull<InnerNode>,
+pub(super) fn reverse_int_seg(
+    nodes: &mut NodeArena,
+    segs: &mut [Segment],
+    seg: u32,
+    a: u32,
+    b: u32,
 ) {
-    let a_pred = (*a.as_ptr()).predecessor;
-    let b_succ = (*b.as_ptr()).successor;
-    (*a.as_ptr()).predecessor = b_succ;
-    (*b.as_ptr()).successor = a_pred;
+    let a_pred = nodes[a].predecessor;
+    let b_succ = nodes[b].successor;
+    nodes[a].predecessor = b_succ;
+    nodes[b].successor = a_pred;
 
-    let (rl, rr) = ((*a.as_ptr()).rank, (*b.as_ptr()).rank);
+    let (rl, rr) = (nodes[a].rank, nodes[b].rank);
     let mut rank = rr;
-    let mut node = *a;
+    let mut node = a;
 
     while rank >= rl {
-        let tmp = (*node.as_ptr()).successor;
-        std::mem::swap(
-            &mut (*node.as_ptr()).successor,
-            &mut (*node.as_ptr()).predecessor,
-        );
-        (*node.as_ptr()).rank = rank;
+        let inner = &mut nodes[node];
+        let tmp = inner.successor;
+        std::mem::swap(&mut inner.successor, &mut inner.predecessor);
+        inner.rank = rank;
         rank -= 1;
 
-        match tmp {
-            Some(next) => node = next,
-            None => break,
+        if tmp == NIL {
+            break;
         }
+        node = tmp;
     }
 
-    change_kin!(a_pred, a, b);
-    change_kin!(b_succ, b, a);
+    change_kin(nodes, a_pred, a, b);
+    change_kin(nodes, b_succ, b, a);
 
-    if (*seg.as_ptr()).first == Some(*a) {
-        (*seg.as_ptr()).first = Some(*b);
-    } else if (*seg.as_ptr()).first == Some(*b) {
-        (*seg.as_ptr()).first = Some(*a);
+    let s = &mut segs[seg as usize];
+    if s.first == a {
+        s.first = b;
+    } else if s.first == b {
+        s.first = a;
     }
 
-    if (*seg.as_ptr()).last == Some(*a) {
-        (*seg.as_ptr()).last = Some(*b);
-    } else if (*seg.as_ptr()).last == Some(*b) {
-        (*seg.as_ptr()).last = Some(*a);
+    if s.last == a {
+        s.last = b;
+    } else if s.last == b {
+        s.last = a;
     }
 }
 
-macro_rules! swap_sym {
-    ($head: ident, $tail:ident, $target:ident, $key:ident) => {
-        match ((*$head.as_ptr()).$target, (*$tail.as_ptr()).$target) {
-            (Some(node_a), Some(node_b)) => {
-                let kin_from_a = (*node_a.as_ptr()).$key;
-                let kin_from_b = (*node_b.as_ptr()).$key;
-
-                match kin_from_a {
-                    Some(kin) => {
-                        if (*kin.as_ptr()).predecessor == (*$head.as_ptr()).$target {
-                            (*kin.as_ptr()).predecessor = (*$tail.as_ptr()).$target;
-                        } else {
-                            (*kin.as_ptr()).successor = (*$tail.as_ptr()).$target;
-                        }
-                    }
-                    None => panic!("Missing pointer"),
-                }
-
-                match kin_from_b {
-                    Some(kin) => {
-                        if (*kin.as_ptr()).predecessor == (*$tail.as_ptr()).$target {
-                            (*kin.as_ptr()).predecessor = (*$head.as_ptr()).$target;
-                        } else {
-                            (*kin.as_ptr()).successor = (*$head.as_ptr()).$target;
-                        }
-                    }
-                    None => panic!("Missing pointer"),
-                }
-
-                (*node_a.as_ptr()).$key = kin_from_b;
-                (*node_b.as_ptr()).$key = kin_from_a;
-            }
-            _ => panic!("Missing pointers"),
-        }
-    };
+/// Returns the node through which the forward traversal of a tour enters a segment.
+#[inline]
+fn entry(seg: &Segment) -> u32 {
+    if seg.reverse {
+        seg.last
+    } else {
+        seg.first
+    }
 }
 
-macro_rules! swap_asym {
-    ($head:ident, $tail:ident) => {
-        match ((*$head.as_ptr()).first, (*$tail.as_ptr()).last) {
-            (Some(node_a), Some(node_b)) => {
-                let a_pred = (*node_a.as_ptr()).predecessor;
-                let b_succ = (*node_b.as_ptr()).successor;
-
-                match a_pred {
-                    Some(pred) => {
-                        if (*pred.as_ptr()).predecessor == (*$head.as_ptr()).first {
-                            (*pred.as_ptr()).predecessor = (*$tail.as_ptr()).last;
-                        } else {
-                            (*pred.as_ptr()).successor = (*$tail.as_ptr()).last;
-                        }
-                    }
-                    None => panic!("Missing pointer"),
-                }
-
-                match b_succ {
-                    Some(succ) => {
-                        if (*succ.as_ptr()).predecessor == (*$tail.as_ptr()).last {
-                            (*succ.as_ptr()).predecessor = (*$head.as_ptr()).first;
-                        } else {
-                            (*succ.as_ptr()).successor = (*$head.as_ptr()).first;
-                        }
-                    }
-                    None => panic!("Missing pointer"),
-                }
-
-                (*node_a.as_ptr()).predecessor = b_succ;
-                (*node_b.as_ptr()).successor = a_pred;
-            }
-            _ => panic!("Missing pointers"),
-        }
-    };
+/// Returns the node through which the forward traversal of a tour leaves a segment.
+#[inline]
+fn exit(seg: &Segment) -> u32 {
+    if seg.reverse {
+        seg.first
+    } else {
+        seg.last
+    }
 }
 
-// TODO: better panic msg.
-pub unsafe fn reverse_segs(from: &NonNull<Segment>, to: &NonNull<Segment>) {
-    let mut a = *from;
-    let mut b = *to;
+/// Links two nodes so that `to` directly succeeds `from` in the forward traversal of a tour.
+#[inline]
+fn link(nodes: &mut NodeArena, segs: &[Segment], from: u32, to: u32) {
+    let (sf, st) = (nodes[from].segment, nodes[to].segment);
+    if sf == NIL || st == NIL {
+        panic!("Node without segment");
+    }
 
-    loop {
-        if a == b {
-            (*a.as_ptr()).reverse();
+    if segs[sf as usize].reverse {
+        nodes[from].predecessor = to;
+    } else {
+        nodes[from].successor = to;
+    }
+
+    if segs[st as usize].reverse {
+        nodes[to].successor = from;
+    } else {
+        nodes[to].predecessor = from;
+    }
+}
+
+/// Reverses the path consisting of the consecutive segments from `from` to `to`.
+///
+/// The segments outside of the path must not be affected by the reversal, i.e. the path
+/// must not cover the entire tour.
+pub(super) fn reverse_segs(nodes: &mut NodeArena, segs: &mut [Segment], from: u32, to: u32) {
+    let (before, after) = (segs[from as usize].prev, segs[to as usize].next);
+    if before == NIL || after == NIL {
+        panic!("Missing neighbouring segments");
+    }
+
+    let (prev_node, next_node) = (exit(&segs[before as usize]), entry(&segs[after as usize]));
+    if prev_node == NIL || next_node == NIL {
+        panic!("Empty first or last nodes in segment.");
+    }
+
+    // The reversed segments take over the ranks in their original order, thus the ranks are
+    // swapped by two cursors moving inwards from both ends of the path.
+    let (mut a, mut b) = (from, to);
+    while a != b {
+        let rank = segs[a as usize].rank;
+        segs[a as usize].rank = segs[b as usize].rank;
+        segs[b as usize].rank = rank;
+
+        if segs[a as usize].next == b {
             break;
         }
-
-        match ((*a.as_ptr()).reverse, (*b.as_ptr()).reverse) {
-            (true, true) | (false, false) => {
-                swap_sym!(a, b, first, predecessor);
-                swap_sym!(a, b, last, successor);
-            }
-            (true, false) | (false, true) => {
-                swap_asym!(a, b);
-                swap_asym!(b, a);
-            }
+        a = segs[a as usize].next;
+        b = segs[b as usize].prev;
+        if a == NIL || b == NIL {
+            panic!("Missing next segment");
         }
+    }
 
-        (*a.as_ptr()).reverse();
-        (*b.as_ptr()).reverse();
-
-        let tmp_next = (*a.as_ptr()).next;
-        let tmp_prev = (*b.as_ptr()).prev;
+    // Relink the segments and their end nodes in reverse order, walking backwards from `to`.
+    let mut prev_seg = before;
+    let mut last_node = prev_node;
+    let mut seg = to;
+    loop {
+        let old_prev = segs[seg as usize].prev;
 
-        std::mem::swap(&mut (*a.as_ptr()).rank, &mut (*b.as_ptr()).rank);
+        let s = &mut segs[seg as usize];
+        s.reverse ^= true;
+        s.prev = prev_seg;
+        let (en, ex) = (entry(s), exit(s));
+        segs[prev_seg as usize].next = seg;
 
-        match ((*a.as_ptr()).prev, (*a.as_ptr()).next) {
-            (Some(prev), Some(next)) => {
-                (*prev.as_ptr()).next = Some(b);
-                (*next.as_ptr()).prev = Some(b);
-            }
-            _ => panic!("Nullpointer"),
-        };
+        if en == NIL || ex == NIL {
+            panic!("Empty first or last nodes in segment.");
+        }
+        link(nodes, segs, last_node, en);
+        last_node = ex;
+        prev_seg = seg;
 
-        match ((*b.as_ptr()).prev, (*b.as_ptr()).next) {
-            (Some(prev), Some(next)) => {
-                (*prev.as_ptr()).next = Some(a);
-                (*next.as_ptr()).prev = Some(a);
-            }
-            _ => panic!("Nullpointer"),
-        };
+        if seg == from {
+            break;
+        }
+        seg = old_prev;
+        if seg == NIL {
+            panic!("Missing previous segment");
+        }
+    }
 
-        (*a.as_ptr()).next = (*b.as_ptr()).next;
-        (*b.as_ptr()).prev = (*a.as_ptr()).prev;
+    segs[prev_seg as usize].next = after;
+    segs[after as usize].prev = prev_seg;
+    link(nodes, segs, last_node, next_node);
+}
 
-        if tmp_next == Some(b) {
-            // a is the neighbour directly in front of b.
-            (*b.as_ptr()).next = Some(a);
-            (*a.as_ptr()).prev = Some(b);
+/// Adds `shift` to the ranks of the nodes from `from` to `to`, walking along their successor
+/// (```succ```) or predecessor links.
+fn shift_ranks(nodes: &mut NodeArena, from: u32, to: u32, succ: bool, shift: i32) {
+    let mut node = from;
+    loop {
+        nodes[node].rank += shift;
+        if node == to {
             break;
-        } else {
-            (*b.as_ptr()).next = tmp_next;
-            (*a.as_ptr()).prev = tmp_prev;
         }
+        node = kin(&nodes[node], succ);
+    }
+}
+
+/// Moves the forward path from `a` to `b` between `x` and the node following it. The path keeps
+/// its direction unless `reverse` is set.
+///
+/// The path must lie in a single segment without covering it entirely. Its nodes join the
+/// segment of `x`, or the segment of the following node if `x` is the exit of its segment and
+/// the other segment is shorter. Ranks are shifted on the shorter side of the gap left by the
+/// path and on the shorter side of the insertion point.
+pub(super) fn splice(
+    nodes: &mut NodeArena,
+    segs: &mut [Segment],
+    a: u32,
+    b: u32,
+    x: u32,
+    reverse: bool,
+) {
+    let s = nodes[a].segment;
+    let s_rev = segs[s as usize].reverse;
+    let (lo, hi) = if s_rev { (b, a) } else { (a, b) };
+    let n_path = nodes[hi].rank - nodes[lo].rank + 1;
+
+    // Close the gap left by the path.
+    let (below, above) = (nodes[lo].predecessor, nodes[hi].successor);
+    if s_rev {
+        link(nodes, segs, above, below);
+    } else {
+        link(nodes, segs, below, above);
+    }
+
+    let (first, last) = (segs[s as usize].first, segs[s as usize].last);
+    if first == lo {
+        segs[s as usize].first = above;
+    } else if last == hi {
+        segs[s as usize].last = below;
+    } else if nodes[last].rank - nodes[hi].rank <= nodes[lo].rank - nodes[first].rank {
+        shift_ranks(nodes, above, last, true, -n_path);
+    } else {
+        shift_ranks(nodes, first, below, true, n_path);
+    }
 
-        match tmp_next {
-            Some(next) => a = next,
-            None => panic!("Missing next segment"),
+    // Make room for the path after x, which becomes the new exit of its segment, or the new
+    // entry of the next one, if x is the exit of its segment.
+    let (head, tail) = if reverse { (b, a) } else { (a, b) };
+    let tx = nodes[x].segment;
+    let tx_rev = segs[tx as usize].reverse;
+    let y = kin(&nodes[x], !tx_rev);
+    let ty = nodes[y].segment;
+
+    let (t, base) = if x == exit(&segs[tx as usize]) {
+        let len = |seg: &Segment| nodes[seg.last].rank - nodes[seg.first].rank;
+        if ty != tx && len(&segs[ty as usize]) < len(&segs[tx as usize]) {
+            let seg = &mut segs[ty as usize];
+            let dir = if seg.reverse { -1 } else { 1 };
+            if seg.reverse {
+                seg.last = head;
+            } else {
+                seg.first = head;
+            }
+            (ty, nodes[y].rank - dir * (n_path + 1))
+        } else {
+            let seg = &mut segs[tx as usize];
+            if seg.reverse {
+                seg.first = tail;
+            } else {
+                seg.last = tail;
+            }
+            (tx, nodes[x].rank)
         }
+    } else {
+        let dir = if tx_rev { -1 } else { 1 };
+        let (en, ex) = (entry(&segs[tx as usize]), exit(&segs[tx as usize]));
+        if (nodes[ex].rank - nodes[y].rank).abs() <= (nodes[x].rank - nodes[en].rank).abs() {
+            shift_ranks(nodes, y, ex, !tx_rev, dir * n_path);
+        } else {
+            shift_ranks(nodes, en, x, !tx_rev, -dir * n_path);
+        }
+        (tx, nodes[x].rank)
+    };
 
-        match tmp_prev {
-            Some(prev) => b = prev,
-            None => panic!("Missing prev segment"),
+    // Move the nodes of the path into the target segment in their new order, swapping their
+    // links wherever their direction relative to the segment changes.
+    let t_rev = segs[t as usize].reverse;
+    let dir = if t_rev { -1 } else { 1 };
+    let (succ, swap) = (s_rev == reverse, s_rev ^ t_rev ^ reverse);
+
+    let mut node = head;
+    for rank in 1..=n_path {
+        let inner = &mut nodes[node];
+        let next = kin(inner, succ);
+        inner.segment = t;
+        inner.rank = base + dir * rank;
+        if swap {
+            std::mem::swap(&mut inner.successor, &mut inner.predecessor);
         }
+        node = next;
     }
-}
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
-pub enum NodeStatus {
-    Active,
-    Fixed,
-    Undefined,
+    link(nodes, segs, x, head);
+    link(nodes, segs, tail, y);
 }
@@ -0,0 +1,111 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use super::TourOrder;
+
+/// Operations which treat a tour order as an undirected cycle, i.e. independently of the node it
+/// starts at and the direction it is traversed in.
+impl TourOrder {
+    /// Rotates the order so that it starts at the given node, and returns ```false``` if the
+    /// order does not contain the node.
+    pub fn rotate_to(&mut self, node: usize) -> bool {
+        match self.order.iter().position(|&x| x == node) {
+            Some(pos) => {
+                self.order.rotate_left(pos);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Reverses the direction of the order while keeping its first node in place, if the node
+    /// right after the first one has a greater index than the last node.
+    pub fn normalize(&mut self) {
+        let len = self.order.len();
+        if len > 2 && self.order[1] > self.order[len - 1] {
+            self.order[1..].reverse();
+        }
+    }
+
+    /// Brings the order into its canonical form, which starts at the node with the smallest index
+    /// and has a normalized direction.
+    ///
+    /// Two orders describe the same cycle if and only if their canonical forms are equal.
+    pub fn canonicalize(&mut self) {
+        if let Some(&min) = self.order.iter().min() {
+            self.rotate_to(min);
+            self.normalize();
+        }
+    }
+
+    /// Returns ```true``` if both orders describe the same cycle, regardless of their starting
+    /// nodes and directions.
+    pub fn is_same_cycle(&self, other: &TourOrder) -> bool {
+        self.len() == other.len() && self.edge_distance(other) == 0
+    }
+
+    /// Returns the iterator over the edges of the cycle, starting with the edge from the first to
+    /// the second node and ending with the edge from the last back to the first node.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let len = self.order.len();
+        (0..len).map(move |idx| (self.order[idx], self.order[(idx + 1) % len]))
+    }
+
+    /// Returns the edges of this order which also appear in `other`.
+    pub fn shared_edges(&self, other: &TourOrder) -> Vec<(usize, usize)> {
+        let kins = other.kins(self.max_node());
+        self.edges()
+            .filter(|(u, v)| is_kin(&kins, *u, *v))
+            .collect()
+    }
+
+    /// Returns the edges of this order which do not appear in `other`.
+    pub fn differing_edges(&self, other: &TourOrder) -> Vec<(usize, usize)> {
+        let kins = other.kins(self.max_node());
+        self.edges()
+            .filter(|(u, v)| !is_kin(&kins, *u, *v))
+            .collect()
+    }
+
+    /// Returns the number of edges in this order which do not appear in `other`.
+    pub fn edge_distance(&self, other: &TourOrder) -> usize {
+        let kins = other.kins(self.max_node());
+        self.edges().filter(|(u, v)| !is_kin(&kins, *u, *v)).count()
+    }
+
+    /// Returns a hash of the cycle, which is equal for all orders describing the same cycle.
+    ///
+    /// The cost of the order is not taken into account.
+    pub fn cycle_hash(&self) -> u64 {
+        let mut canonical = TourOrder::with_ord(self.order.clone());
+        canonical.canonicalize();
+
+        let mut hasher = DefaultHasher::new();
+        canonical.order.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn max_node(&self) -> Option<usize> {
+        self.order.iter().max().copied()
+    }
+
+    /// Returns the two neighbours of each node in the cycle, for all nodes up to `max_node`.
+    fn kins(&self, max_node: Option<usize>) -> Vec<[usize; 2]> {
+        let len = self.max_node().max(max_node).map_or(0, |node| node + 1);
+        let mut kins = vec![[usize::MAX; 2]; len];
+
+        let n = self.order.len();
+        for (idx, &node) in self.order.iter().enumerate() {
+            kins[node] = [self.order[(n + idx - 1) % n], self.order[(idx + 1) % n]];
+        }
+
+        kins
+    }
+}
+
+#[inline]
+fn is_kin(kins: &[[usize; 2]], u: usize, v: usize) -> bool {
+    kins[u].contains(&v)
+}
@@ -0,0 +1,131 @@
+use crate::Scalar;
+
+use super::{Tour, TourNode};
+
+/// The outcome of [`prepare`].
+pub(super) enum Splice {
+    /// The move is complete and changed the total distance by the given amount.
+    Done(Scalar),
+    /// The path still has to be moved, which will change the total distance by the given amount.
+    Pending(Scalar),
+}
+
+/// Checks the arguments of a segment move and completes the move if the path already follows
+/// `to`, in which case it is at most reversed in place by a flip.
+///
+/// Otherwise, returns the change in the total distance of moving the path from `first` to `last`
+/// between `to` and its successor, before any node is touched.
+pub(super) fn prepare<T>(
+    tour: &mut T,
+    first: &TourNode,
+    last: &TourNode,
+    to: &TourNode,
+    reverse: bool,
+) -> Splice
+where
+    T: Tour + ?Sized,
+{
+    let (p, n, d) = neighbours(tour, first, last, to);
+
+    assert!(
+        !tour.between(first, to, last),
+        "Target node {} lies within the moved segment.",
+        to.index()
+    );
+
+    if *to == p {
+        if !reverse {
+            return Splice::Done(0.);
+        }
+
+        let delta = tour.distance(&p, last) + tour.distance(first, &n)
+            - tour.distance(&p, first)
+            - tour.distance(last, &n);
+        reverse_path(tour, &p, first, last, &n);
+        return Splice::Done(delta);
+    }
+
+    let (head, tail) = if reverse {
+        (last, first)
+    } else {
+        (first, last)
+    };
+
+    Splice::Pending(
+        tour.distance(&p, &n) + tour.distance(to, head) + tour.distance(tail, &d)
+            - tour.distance(&p, first)
+            - tour.distance(last, &n)
+            - tour.distance(to, &d),
+    )
+}
+
+/// Moves the path from `first` to `last` between `to` and its successor by a sequence of flips,
+/// for a move which [`prepare`] left pending.
+///
+/// With `p` and `n` being the neighbours of the path and `d` the successor of `to`, the tour
+/// `p [first..last] [n..to] d` is turned into `p [n..to] [first..last] d` by reversing both
+/// blocks and then their union, or into `p [n..to] [last..first] d` by reversing the second
+/// block and then the union.
+pub(super) fn move_by_flips<T>(
+    tour: &mut T,
+    first: &TourNode,
+    last: &TourNode,
+    to: &TourNode,
+    reverse: bool,
+) where
+    T: Tour + ?Sized,
+{
+    let (p, n, d) = neighbours(tour, first, last, to);
+
+    if reverse {
+        reverse_path(tour, last, &n, to, &d);
+        reverse_path(tour, &p, first, &n, &d);
+    } else {
+        reverse_path(tour, &p, first, last, &n);
+        reverse_path(tour, first, &n, to, &d);
+        reverse_path(tour, &p, last, &n, &d);
+    }
+}
+
+/// Returns the predecessor of `first`, the successor of `last` and the successor of `to`.
+fn neighbours<T>(
+    tour: &T,
+    first: &TourNode,
+    last: &TourNode,
+    to: &TourNode,
+) -> (TourNode, TourNode, TourNode)
+where
+    T: Tour + ?Sized,
+{
+    match (
+        tour.predecessor(first),
+        tour.successor(last),
+        tour.successor(to),
+    ) {
+        (Some(p), Some(n), Some(d)) => (p, n, d),
+        _ => panic!("Node not found while moving segment."),
+    }
+}
+
+/// Reverses the path from `first` to `last`, which lies between the nodes `before` and `after`,
+/// regardless of the direction in which the tour currently traverses the path.
+fn reverse_path<T>(
+    tour: &mut T,
+    before: &TourNode,
+    first: &TourNode,
+    last: &TourNode,
+    after: &TourNode,
+) where
+    T: Tour + ?Sized,
+{
+    // Reversing a single node, or all nodes but one, does not change the tour.
+    if first == last || before == after {
+        return;
+    }
+
+    if tour.successor(before) == Some(*first) {
+        tour.flip(before, first, last, after);
+    } else {
+        tour.flip(first, before, after, last);
+    }
+}
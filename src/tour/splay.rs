@@ -0,0 +1,656 @@
+use std::cell::{Cell, RefCell};
+
+use crate::{
+    data::{DataStore, GetIndex, NodeStore},
+    Scalar,
+};
+
+use super::{
+    between,
+    node::NodeArena,
+    segment::{self, Splice},
+    validate::{debug_check_distance, flip_delta, validate_cycle},
+    InvariantError, NodeRel, Tour, TourIter, TourNode, TourOrder, UpdateTourError,
+};
+
+const NIL: usize = usize::MAX;
+
+/// A tour which keeps its nodes in a splay tree keyed by their positions in the tour [4].
+///
+/// Each tree node carries a reversal bit, which is lazily pushed down to its children. A flip
+/// thus only splits the tree around the affected path, marks that path as reversed and joins
+/// the parts again. All operations, including `successor`, `predecessor`, `between` and `flip`,
+/// take amortised O(log N) time, which makes the representation suitable for very large
+/// instances.
+///
+/// Since even read-only queries restructure the tree, the links between tree nodes are kept in
+/// [`Cell`]s.
+#[derive(Clone, Debug)]
+pub struct SplayTree {
+    store: NodeStore,
+    nodes: NodeArena,
+    links: Vec<Cell<Link>>,
+    root: Cell<usize>,
+    /// Buffer for the paths from tree nodes to the root, reused across splay operations.
+    path: RefCell<Vec<usize>>,
+    total_dist: Scalar,
+    rev: bool,
+}
+
+/// The links of a tree node. Tree nodes share their indices with the nodes of a tour.
+#[derive(Clone, Copy, Debug)]
+struct Link {
+    parent: usize,
+    left: usize,
+    right: usize,
+    size: usize,
+    rev: bool,
+}
+
+impl Default for Link {
+    fn default() -> Self {
+        Self {
+            parent: NIL,
+            left: NIL,
+            right: NIL,
+            size: 1,
+            rev: false,
+        }
+    }
+}
+
+impl SplayTree {
+    pub fn new<M>(store: &DataStore<M>) -> Self {
+        let node_store = store.store();
+        let n_nodes = store.len();
+
+        let nodes = NodeArena::new(n_nodes);
+
+        let mut result = Self {
+            store: node_store,
+            nodes,
+            links: vec![Cell::new(Link::default()); n_nodes],
+            root: Cell::new(NIL),
+            path: RefCell::new(Vec::new()),
+            total_dist: 0.,
+            rev: false,
+        };
+
+        result.apply(&TourOrder::with_nat_ord(n_nodes)).unwrap();
+
+        result
+    }
+
+    #[inline]
+    fn link(&self, x: usize) -> Link {
+        self.links[x].get()
+    }
+
+    #[inline]
+    fn update_link<F>(&self, x: usize, f: F)
+    where
+        F: FnOnce(&mut Link),
+    {
+        let mut link = self.links[x].get();
+        f(&mut link);
+        self.links[x].set(link);
+    }
+
+    #[inline]
+    fn size(&self, x: usize) -> usize {
+        if x == NIL {
+            0
+        } else {
+            self.link(x).size
+        }
+    }
+
+    #[inline]
+    fn set_parent(&self, x: usize, parent: usize) {
+        if x != NIL {
+            self.update_link(x, |l| l.parent = parent);
+        }
+    }
+
+    #[inline]
+    fn toggle_rev(&self, x: usize) {
+        if x != NIL {
+            self.update_link(x, |l| l.rev ^= true);
+        }
+    }
+
+    /// Recomputes the size of a subtree from its children.
+    #[inline]
+    fn pull(&self, x: usize) {
+        let link = self.link(x);
+        let size = 1 + self.size(link.left) + self.size(link.right);
+        self.update_link(x, |l| l.size = size);
+    }
+
+    /// Pushes the reversal bit of a tree node down to its children.
+    #[inline]
+    fn push(&self, x: usize) {
+        let link = self.link(x);
+        if link.rev {
+            self.toggle_rev(link.left);
+            self.toggle_rev(link.right);
+            self.update_link(x, |l| {
+                std::mem::swap(&mut l.left, &mut l.right);
+                l.rev = false;
+            });
+        }
+    }
+
+    fn rotate(&self, x: usize) {
+        let p = self.link(x).parent;
+        let g = self.link(p).parent;
+
+        if self.link(p).left == x {
+            let b = self.link(x).right;
+            self.update_link(p, |l| l.left = b);
+            self.set_parent(b, p);
+            self.update_link(x, |l| l.right = p);
+        } else {
+            let b = self.link(x).left;
+            self.update_link(p, |l| l.right = b);
+            self.set_parent(b, p);
+            self.update_link(x, |l| l.left = p);
+        }
+
+        self.set_parent(p, x);
+        self.set_parent(x, g);
+        if g != NIL {
+            self.update_link(g, |l| {
+                if l.left == p {
+                    l.left = x;
+                } else {
+                    l.right = x;
+                }
+            });
+        }
+
+        self.pull(p);
+        self.pull(x);
+    }
+
+    /// Moves a tree node to the root of its tree.
+    fn splay(&self, x: usize) {
+        // Reversal bits must be pushed down along the path before rotating.
+        let mut path = self.path.borrow_mut();
+        path.clear();
+        let mut y = x;
+        while y != NIL {
+            path.push(y);
+            y = self.link(y).parent;
+        }
+        for &y in path.iter().rev() {
+            self.push(y);
+        }
+        drop(path);
+
+        loop {
+            let p = self.link(x).parent;
+            if p == NIL {
+                break;
+            }
+
+            let g = self.link(p).parent;
+            if g != NIL {
+                let zigzig = (self.link(g).left == p) == (self.link(p).left == x);
+                if zigzig {
+                    self.rotate(p);
+                } else {
+                    self.rotate(x);
+                }
+            }
+            self.rotate(x);
+        }
+    }
+
+    /// Returns the tree node at the given position and moves it to the root.
+    fn select(&self, root: usize, mut pos: usize) -> usize {
+        let mut x = root;
+        loop {
+            self.push(x);
+            let link = self.link(x);
+            let left = self.size(link.left);
+            if pos < left {
+                x = link.left;
+            } else if pos == left {
+                break;
+            } else {
+                pos -= left + 1;
+                x = link.right;
+            }
+        }
+
+        self.splay(x);
+        x
+    }
+
+    /// Returns the left- or right-most tree node in the subtree of `x` and moves it to the root.
+    fn extreme(&self, mut x: usize, leftmost: bool) -> usize {
+        loop {
+            self.push(x);
+            let link = self.link(x);
+            let next = if leftmost { link.left } else { link.right };
+            if next == NIL {
+                break;
+            }
+            x = next;
+        }
+
+        self.splay(x);
+        x
+    }
+
+    /// Splits a tree into two trees, of which the first one contains `k` nodes.
+    fn split(&self, root: usize, k: usize) -> (usize, usize) {
+        if k == 0 {
+            return (NIL, root);
+        }
+        if k >= self.size(root) {
+            return (root, NIL);
+        }
+
+        let x = self.select(root, k);
+        let left = self.link(x).left;
+        self.set_parent(left, NIL);
+        self.update_link(x, |l| l.left = NIL);
+        self.pull(x);
+
+        (left, x)
+    }
+
+    /// Joins two trees, where all nodes of the tree `left` precede those of the tree `right`.
+    fn join(&self, left: usize, right: usize) -> usize {
+        if left == NIL {
+            return right;
+        }
+
+        let x = self.extreme(left, false);
+        self.update_link(x, |l| l.right = right);
+        self.set_parent(right, x);
+        self.pull(x);
+
+        x
+    }
+
+    /// Returns the position of a node in the in-order traversal of the tree.
+    #[inline]
+    fn pos(&self, node: &TourNode) -> usize {
+        let x = node.index();
+        self.splay(x);
+        self.root.set(x);
+        self.size(self.link(x).left)
+    }
+
+    /// Returns the node which directly follows (```forward```) or precedes a node in the
+    /// in-order traversal of the tree. The traversal wraps around at both ends.
+    fn adjacent(&self, node: &TourNode, forward: bool) -> Option<TourNode> {
+        if !self.nodes.contains(node) {
+            return None;
+        }
+        let x = node.index();
+        self.splay(x);
+
+        let link = self.link(x);
+        let child = if forward { link.right } else { link.left };
+        let y = if child == NIL {
+            self.extreme(x, forward)
+        } else {
+            self.extreme(child, forward)
+        };
+        self.root.set(y);
+
+        self.nodes.get(y)
+    }
+
+    /// Reverses the nodes in the cyclic range of positions from `from` to `to`, inclusively.
+    fn reverse(&self, from: usize, to: usize) {
+        if from > to {
+            // A range which wraps around the ends is reversed by reversing the entire tree and
+            // restoring the order of the remaining nodes, which are now in the middle. This
+            // yields the same cycle, only rotated.
+            let len = self.nodes.len();
+            self.toggle_rev(self.root.get());
+            if from > to + 1 {
+                self.reverse(len - from, len - to - 2);
+            }
+            return;
+        }
+
+        let (left, rest) = self.split(self.root.get(), from);
+        let (mid, right) = self.split(rest, to - from + 1);
+        self.toggle_rev(mid);
+        let root = self.join(self.join(left, mid), right);
+        self.root.set(root);
+    }
+
+    /// Builds a perfectly balanced tree from a slice of tree nodes and returns its root.
+    fn build(&self, order: &[usize], parent: usize) -> usize {
+        if order.is_empty() {
+            return NIL;
+        }
+
+        let mid = order.len() / 2;
+        let x = order[mid];
+        let left = self.build(&order[..mid], x);
+        let right = self.build(&order[mid + 1..], x);
+        self.links[x].set(Link {
+            parent,
+            left,
+            right,
+            size: order.len(),
+            rev: false,
+        });
+
+        x
+    }
+}
+
+impl Tour for SplayTree {
+    fn apply(&mut self, tour: &TourOrder) -> Result<(), UpdateTourError> {
+        let order = tour.order();
+        let len = self.nodes.len();
+        tour.validate(len)?;
+
+        self.rev = false;
+        self.total_dist = 0.;
+        for (pos, idx) in order.iter().enumerate() {
+            self.total_dist += self.store.cost(idx, &order[(pos + 1) % len]);
+        }
+
+        let root = self.build(order, NIL);
+        self.root.set(root);
+
+        Ok(())
+    }
+
+    #[inline]
+    fn between(&self, from: &TourNode, mid: &TourNode, to: &TourNode) -> bool {
+        let (f, m, t) = (self.pos(from), self.pos(mid), self.pos(to));
+        if self.rev {
+            between(t, m, f)
+        } else {
+            between(f, m, t)
+        }
+    }
+
+    #[inline]
+    fn between_at(&self, from_index: usize, mid_index: usize, to_index: usize) -> bool {
+        match (
+            self.get(from_index),
+            self.get(mid_index),
+            self.get(to_index),
+        ) {
+            (Some(from), Some(mid), Some(to)) => self.between(&from, &mid, &to),
+            _ => false,
+        }
+    }
+
+    #[inline]
+    fn distance_at<I>(&self, a: &I, b: &I) -> Scalar
+    where
+        I: GetIndex + PartialEq + Eq,
+    {
+        self.store.cost(a, b)
+    }
+
+    #[inline]
+    fn coords(&self, node: &TourNode) -> &[Scalar] {
+        self.store.coords(&node.index())
+    }
+
+    #[inline]
+    fn flip_at(&mut self, from_a: usize, to_a: usize, from_b: usize, to_b: usize) {
+        if let (Some(fa), Some(ta), Some(fb), Some(tb)) = (
+            self.get(from_a),
+            self.get(to_a),
+            self.get(from_b),
+            self.get(to_b),
+        ) {
+            self.flip(&fa, &ta, &fb, &tb);
+        }
+    }
+
+    fn flip(&mut self, from_a: &TourNode, to_a: &TourNode, from_b: &TourNode, to_b: &TourNode) {
+        let (fa, ta, fb, tb) = (
+            self.pos(from_a),
+            self.pos(to_a),
+            self.pos(from_b),
+            self.pos(to_b),
+        );
+
+        // The forward paths (to_a, from_b) and (to_b, from_a) as ranges of positions.
+        let (p1, p2) = if self.rev {
+            ((fb, ta), (fa, tb))
+        } else {
+            ((ta, fb), (tb, fa))
+        };
+
+        // Reversing either path results in the same tour, thus the shorter one is chosen.
+        let len = self.nodes.len();
+        if (p1.1 + len - p1.0) % len <= (p2.1 + len - p2.0) % len {
+            self.reverse(p1.0, p1.1);
+        } else {
+            self.reverse(p2.0, p2.1);
+        }
+
+        self.total_dist += flip_delta(self, from_a, to_a, from_b, to_b);
+
+        debug_check_distance(self);
+
+        #[cfg(feature = "validate")]
+        super::validate::check_flip(self, from_a, to_a, from_b, to_b);
+    }
+
+    /// Cuts the path out of the tree, marks it as reversed if needed and joins it back in after
+    /// `to`, which takes amortised O(log N) time regardless of the length of the path.
+    fn move_segment(
+        &mut self,
+        first: &TourNode,
+        last: &TourNode,
+        to: &TourNode,
+        reverse: bool,
+    ) -> Scalar {
+        let delta = match segment::prepare(self, first, last, to, reverse) {
+            Splice::Done(delta) => return delta,
+            Splice::Pending(delta) => delta,
+        };
+
+        // The path as a range of positions, and the position after which it is inserted.
+        let len = self.nodes.len();
+        let (lo, hi, at) = if self.rev {
+            (
+                self.pos(last),
+                self.pos(first),
+                (self.pos(to) + len - 1) % len,
+            )
+        } else {
+            (self.pos(first), self.pos(last), self.pos(to))
+        };
+        let n_path = (hi + len - lo) % len + 1;
+
+        // Rotating the nodes so that the path comes first yields the same cycle.
+        let (left, right) = self.split(self.root.get(), lo);
+        let (path, rest) = self.split(self.join(right, left), n_path);
+
+        // The path keeps its order in the tree unless it is reversed.
+        if reverse {
+            self.toggle_rev(path);
+        }
+
+        let at = (at + len - lo) % len - n_path;
+        let (left, right) = self.split(rest, at + 1);
+        let root = self.join(self.join(left, path), right);
+        self.root.set(root);
+
+        self.total_dist += delta;
+
+        debug_check_distance(self);
+
+        #[cfg(feature = "validate")]
+        super::validate::check_move(self, first, last, to);
+
+        delta
+    }
+
+    #[inline]
+    fn get(&self, index: usize) -> Option<TourNode> {
+        self.nodes.get(index)
+    }
+
+    #[inline]
+    fn relation(&self, base: &TourNode, targ: &TourNode) -> NodeRel {
+        if self.successor(base) == Some(*targ) {
+            NodeRel::Predecessor
+        } else if self.predecessor(base) == Some(*targ) {
+            NodeRel::Successor
+        } else {
+            NodeRel::None
+        }
+    }
+
+    #[inline]
+    fn successor(&self, kin: &TourNode) -> Option<TourNode> {
+        self.adjacent(kin, !self.rev)
+    }
+
+    #[inline]
+    fn successor_at(&self, kin_index: usize) -> Option<TourNode> {
+        match self.nodes.get(kin_index) {
+            Some(kin) => self.successor(&kin),
+            None => None,
+        }
+    }
+
+    #[inline]
+    fn predecessor(&self, kin: &TourNode) -> Option<TourNode> {
+        self.adjacent(kin, self.rev)
+    }
+
+    #[inline]
+    fn predecessor_at(&self, kin_index: usize) -> Option<TourNode> {
+        match self.nodes.get(kin_index) {
+            Some(kin) => self.predecessor(&kin),
+            None => None,
+        }
+    }
+
+    #[inline]
+    fn rev(&mut self) {
+        self.rev ^= true;
+    }
+
+    fn tour_order(&self) -> TourOrder {
+        let len = self.nodes.len();
+        if len == 0 {
+            return TourOrder::default();
+        }
+
+        // In-order traversal of the tree.
+        let mut internal = Vec::with_capacity(len);
+        let mut stack = Vec::new();
+        let mut x = self.root.get();
+        while x != NIL || !stack.is_empty() {
+            while x != NIL {
+                self.push(x);
+                stack.push(x);
+                x = self.link(x).left;
+            }
+            if let Some(y) = stack.pop() {
+                internal.push(y);
+                x = self.link(y).right;
+            }
+        }
+
+        let start = internal.iter().position(|&x| x == 0).unwrap_or(0);
+        let order: Vec<usize> = (0..len)
+            .map(|ii| {
+                if self.rev {
+                    internal[(start + len - ii) % len]
+                } else {
+                    internal[(start + ii) % len]
+                }
+            })
+            .collect();
+
+        let mut result = TourOrder::with_ord(order);
+        result.set_cost(self.measure(&result));
+        result
+    }
+
+    fn measure(&self, to: &TourOrder) -> Scalar {
+        if !to.is_empty() && self.len() == to.len() {
+            let v = to.order();
+            let mut cost = self.distance_at(v.first().unwrap(), v.last().unwrap());
+            for pair in v.windows(2) {
+                cost += self.distance_at(&pair[0], &pair[1]);
+            }
+            cost
+        } else {
+            0.
+        }
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    #[inline]
+    fn total_distance(&self) -> Scalar {
+        self.total_dist
+    }
+
+    fn validate(&self) -> Result<(), InvariantError> {
+        let root = self.root.get();
+        if self.nodes.is_empty() {
+            return Ok(());
+        }
+        if root == NIL || self.link(root).parent != NIL {
+            return Err(InvariantError::BrokenSegment { segment: root });
+        }
+
+        let mut count = 0;
+        let mut stack = vec![root];
+        while let Some(x) = stack.pop() {
+            count += 1;
+            if count > self.nodes.len() {
+                break;
+            }
+
+            let link = self.link(x);
+            if link.size != 1 + self.size(link.left) + self.size(link.right) {
+                return Err(InvariantError::BrokenSegment { segment: x });
+            }
+            for &child in &[link.left, link.right] {
+                if child != NIL {
+                    if self.link(child).parent != x {
+                        return Err(InvariantError::BrokenSegment { segment: x });
+                    }
+                    stack.push(child);
+                }
+            }
+        }
+
+        if count != self.nodes.len() || self.size(root) != count {
+            return Err(InvariantError::NotACycle {
+                expected: self.nodes.len(),
+                received: count,
+            });
+        }
+
+        validate_cycle(self)
+    }
+
+    fn itr(&self) -> TourIter {
+        self.nodes.itr()
+    }
+}
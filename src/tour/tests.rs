@@ -63,6 +63,229 @@ fn test_between() {
     assert!(!between(5, 3, 1)); // false
 }
 
+#[test]
+fn test_tour_order_canonical() {
+    let mut order = TourOrder::with_ord(vec![3, 1, 4, 0, 2]);
+    assert!(order.rotate_to(2));
+    assert_eq!(&vec![2, 3, 1, 4, 0], order.order());
+    assert!(!order.rotate_to(7));
+
+    // 2 -> 3 ... 0 -> 2 is turned into 2 -> 0 ... 3 -> 2.
+    order.normalize();
+    assert_eq!(&vec![2, 0, 4, 1, 3], order.order());
+
+    order.canonicalize();
+    assert_eq!(&vec![0, 2, 3, 1, 4], order.order());
+
+    let mut empty = TourOrder::new();
+    empty.canonicalize();
+    assert!(empty.is_empty());
+}
+
+#[test]
+fn test_tour_order_edges() {
+    let a = TourOrder::with_ord(vec![0, 1, 2, 3, 4, 5]);
+    let rotated = TourOrder::with_ord(vec![3, 4, 5, 0, 1, 2]);
+    let reversed = TourOrder::with_ord(vec![2, 1, 0, 5, 4, 3]);
+    let b = TourOrder::with_ord(vec![0, 1, 4, 3, 2, 5]);
+
+    assert_eq!(
+        vec![(0, 1), (1, 2), (2, 3), (3, 4), (4, 5), (5, 0)],
+        a.edges().collect::<Vec<_>>()
+    );
+
+    for other in &[&rotated, &reversed] {
+        assert!(a.is_same_cycle(other));
+        assert_eq!(0, a.edge_distance(other));
+        assert_eq!(a.cycle_hash(), other.cycle_hash());
+    }
+
+    assert!(!a.is_same_cycle(&b));
+    assert_ne!(a.cycle_hash(), b.cycle_hash());
+    assert_eq!(vec![(0, 1), (2, 3), (3, 4), (5, 0)], a.shared_edges(&b));
+    assert_eq!(vec![(1, 2), (4, 5)], a.differing_edges(&b));
+    assert_eq!(2, a.edge_distance(&b));
+    assert_eq!(2, b.edge_distance(&a));
+
+    // Nodes missing in the other order do not share any edge.
+    assert_eq!(2, TourOrder::with_ord(vec![0, 1, 7]).edge_distance(&a));
+}
+
+#[allow(dead_code, unused_imports)]
+mod test_array {
+    use super::*;
+
+    use crate::tour::{tests::create_store, Array};
+
+    #[test]
+    fn test_apply() {
+        let mut tour = Array::new(&create_store(10));
+        test_suite::apply(&mut tour);
+    }
+
+    #[test]
+    fn test_total_dist() {
+        let mut tour = Array::new(&create_store(4));
+        test_suite::total_dist(&mut tour);
+    }
+
+    #[test]
+    fn test_between() {
+        let mut tour = Array::new(&create_store(10));
+        test_suite::between(&mut tour);
+    }
+
+    #[test]
+    fn test_traverse() {
+        let mut tour = Array::new(&create_store(10));
+        test_suite::traverse(&mut tour);
+    }
+
+    #[test]
+    fn test_flip_cases() {
+        let mut tour = Array::new(&create_store(100));
+        test_suite::flip(&mut tour);
+    }
+
+    #[test]
+    fn test_flip_random() {
+        let mut tour = Array::new(&create_store(30));
+        test_suite::flip_random(&mut tour);
+
+        let mut tour = Array::new(&create_store(17));
+        test_suite::flip_random(&mut tour);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut tour = Array::new(&create_store(30));
+        test_suite::validate(&mut tour);
+    }
+
+    #[test]
+    fn test_move_segment() {
+        let mut tour = Array::new(&create_store(30));
+        test_suite::move_segment(&mut tour);
+    }
+
+    #[test]
+    fn test_journal() {
+        test_suite::journal(Array::new(&create_store(30)));
+    }
+
+    #[test]
+    fn test_clone() {
+        test_suite::clone(Array::new(&create_store(30)));
+    }
+}
+
+#[allow(dead_code, unused_imports)]
+mod test_splay {
+    use super::*;
+
+    use crate::tour::{tests::create_store, SplayTree};
+
+    #[test]
+    fn test_apply() {
+        let mut tour = SplayTree::new(&create_store(10));
+        test_suite::apply(&mut tour);
+    }
+
+    #[test]
+    fn test_total_dist() {
+        let mut tour = SplayTree::new(&create_store(4));
+        test_suite::total_dist(&mut tour);
+    }
+
+    #[test]
+    fn test_between() {
+        let mut tour = SplayTree::new(&create_store(10));
+        test_suite::between(&mut tour);
+    }
+
+    #[test]
+    fn test_traverse() {
+        let mut tour = SplayTree::new(&create_store(10));
+        test_suite::traverse(&mut tour);
+    }
+
+    #[test]
+    fn test_flip_cases() {
+        let mut tour = SplayTree::new(&create_store(100));
+        test_suite::flip(&mut tour);
+    }
+
+    #[test]
+    fn test_flip_random() {
+        let mut tour = SplayTree::new(&create_store(30));
+        test_suite::flip_random(&mut tour);
+
+        let mut tour = SplayTree::new(&create_store(17));
+        test_suite::flip_random(&mut tour);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut tour = SplayTree::new(&create_store(30));
+        test_suite::validate(&mut tour);
+    }
+
+    #[test]
+    fn test_move_segment() {
+        let mut tour = SplayTree::new(&create_store(30));
+        test_suite::move_segment(&mut tour);
+    }
+
+    #[test]
+    fn test_journal() {
+        test_suite::journal(SplayTree::new(&create_store(30)));
+    }
+
+    #[test]
+    fn test_clone() {
+        test_suite::clone(SplayTree::new(&create_store(30)));
+    }
+}
+
+#[allow(dead_code, unused_imports)]
+mod test_impltor {
+    use super::*;
+
+    use crate::tour::{tests::create_store, TourImpltor, TourKind};
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(TourKind::Array, TourKind::Auto.resolve(10));
+        assert_eq!(
+            TourKind::TwoLevelList,
+            TourKind::Auto.resolve(TourKind::ARRAY_MAX_NODES + 1)
+        );
+        assert_eq!(
+            TourKind::SplayTree,
+            TourKind::Auto.resolve(TourKind::TLL_MAX_NODES + 1)
+        );
+        assert_eq!(TourKind::SplayTree, TourKind::SplayTree.resolve(10));
+    }
+
+    #[test]
+    fn test_flip_random() {
+        for &kind in &[TourKind::Array, TourKind::TwoLevelList, TourKind::SplayTree] {
+            let mut tour = TourImpltor::new(kind, &create_store(30), 6);
+            test_suite::flip_random(&mut tour);
+        }
+    }
+
+    #[test]
+    fn test_clone() {
+        use crate::tour::Journal;
+
+        for &kind in &[TourKind::Array, TourKind::TwoLevelList, TourKind::SplayTree] {
+            test_suite::clone(TourImpltor::new(kind, &create_store(30), 6));
+            test_suite::clone(Journal::new(TourImpltor::new(kind, &create_store(30), 6)));
+        }
+    }
+}
+
 #[allow(dead_code, unused_imports)]
 mod test_tll {
     use std::collections::HashMap;
@@ -72,7 +295,7 @@ mod test_tll {
     use crate::tour::{
         tests::{create_store, test_tour_order},
         tll::TwoLevelList,
-        STree, Tour, TourIter, TourOrder,
+        STree, Tour, TourIter, TourNode, TourOrder,
     };
 
     #[test]
@@ -93,11 +316,105 @@ mod test_tll {
         test_suite::between(&mut tour);
     }
 
+    #[test]
+    fn test_traverse() {
+        let mut tour = TwoLevelList::new(&create_store(10), 3);
+        test_suite::traverse(&mut tour);
+    }
+
     #[test]
     fn test_flip_cases() {
         let mut tour = TwoLevelList::new(&create_store(100), 10);
         test_suite::flip(&mut tour);
     }
+
+    #[test]
+    fn test_flip_random() {
+        let mut tour = TwoLevelList::new(&create_store(30), 6);
+        test_suite::flip_random(&mut tour);
+
+        let mut tour = TwoLevelList::new(&create_store(17), 4);
+        test_suite::flip_random(&mut tour);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut tour = TwoLevelList::new(&create_store(30), 6);
+        test_suite::validate(&mut tour);
+    }
+
+    #[test]
+    fn test_move_segment() {
+        let mut tour = TwoLevelList::new(&create_store(30), 6);
+        test_suite::move_segment(&mut tour);
+
+        let mut tour = TwoLevelList::new(&create_store(17), 4);
+        test_suite::move_segment(&mut tour);
+
+        // All nodes in a single segment.
+        let mut tour = TwoLevelList::new(&create_store(10), 10);
+        test_suite::move_segment(&mut tour);
+    }
+
+    #[test]
+    fn test_journal() {
+        test_suite::journal(TwoLevelList::new(&create_store(30), 6));
+    }
+
+    #[test]
+    fn test_clone() {
+        test_suite::clone(TwoLevelList::new(&create_store(30), 6));
+    }
+
+    #[test]
+    fn test_default_groupsize() {
+        assert_eq!(1, TwoLevelList::default_groupsize(0));
+        assert_eq!(10, TwoLevelList::default_groupsize(100));
+        assert_eq!(11, TwoLevelList::default_groupsize(101));
+
+        let tour = TwoLevelList::with_default_groupsize(&create_store(50));
+        assert_eq!(8, tour.groupsize());
+        assert_eq!(Ok(()), tour.validate());
+
+        let tour = TwoLevelList::new(&create_store(50), 0);
+        assert_eq!(8, tour.groupsize());
+    }
+
+    #[test]
+    fn test_rebalance() {
+        use crate::tour::Traverse;
+        use rand::{Rng, SeedableRng};
+
+        let n_nodes = 100;
+        let mut tour = TwoLevelList::new(&create_store(n_nodes), 5);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+
+        for _ in 0..300 {
+            let (a, c) = (rng.gen_range(0..n_nodes), rng.gen_range(0..n_nodes));
+            let b = tour.successor_at(a).unwrap().index();
+            let d = tour.successor_at(c).unwrap().index();
+            if a != c && b != c && a != d {
+                tour.flip_at(a, b, c, d);
+            }
+            if rng.gen_bool(0.1) {
+                tour.rev();
+            }
+        }
+
+        let start = tour.get(0).unwrap();
+        let expected: Vec<TourNode> = tour.traverse(&start).collect();
+        let dist = tour.total_distance();
+
+        tour.rebalance();
+        assert_eq!(Ok(()), tour.validate());
+        assert_eq!(expected, tour.traverse(&start).collect::<Vec<_>>());
+        assert_eq!(dist, tour.total_distance());
+
+        // All segments are filled up to the group size again.
+        for seg in tour.segments.iter() {
+            assert_eq!(5, tour.segment_len(seg));
+        }
+    }
 }
 
 #[cfg(test)]
@@ -108,7 +425,7 @@ use float_cmp::approx_eq;
 mod test_suite {
     use crate::{
         combine_range,
-        tour::{tests::test_tour_order, Tour, TourOrder},
+        tour::{tests::test_tour_order, Tour, TourOrder, UpdateTourError},
         tour_order, Scalar,
     };
 
@@ -116,6 +433,36 @@ mod test_suite {
         let expected = TourOrder::with_ord(vec![3, 0, 4, 1, 6, 8, 7, 9, 5, 2]);
         assert!(tour.apply(&expected).is_ok());
         test_tour_order(tour, &expected);
+
+        // Orders which are not permutations are rejected without touching the tour.
+        let duplicated = TourOrder::with_ord(vec![3, 0, 4, 1, 6, 8, 7, 9, 5, 3]);
+        assert!(matches!(
+            tour.apply(&duplicated),
+            Err(UpdateTourError::InvalidTourOrder {
+                position: 9,
+                value: 3
+            })
+        ));
+
+        let out_of_range = TourOrder::with_ord(vec![3, 0, 10, 1, 6, 8, 7, 9, 5, 2]);
+        assert!(matches!(
+            tour.apply(&out_of_range),
+            Err(UpdateTourError::InvalidTourOrder {
+                position: 2,
+                value: 10
+            })
+        ));
+
+        assert!(matches!(
+            tour.apply(&TourOrder::with_nat_ord(9)),
+            Err(UpdateTourError::TourLenMismatched {
+                expected: 10,
+                received: 9
+            })
+        ));
+
+        test_tour_order(tour, &expected);
+        assert_eq!(Ok(()), tour.validate());
     }
 
     pub fn total_dist(tour: &mut impl Tour) {
@@ -168,6 +515,315 @@ mod test_suite {
         assert!(tour.between_at(3, 8, 5)); // true
         assert!(tour.between_at(8, 5, 3)); // true
         assert!(!tour.between_at(8, 3, 5)); // false
+
+        // Coinciding nodes, also within a reversed path and a reversed tour.
+        for _ in 0..2 {
+            assert!(tour.between_at(4, 4, 8)); // true
+            assert!(tour.between_at(8, 4, 4)); // true
+            assert!(!tour.between_at(4, 8, 4)); // false
+            tour.rev();
+        }
+    }
+
+    pub fn traverse(tour: &mut impl Tour) {
+        use crate::tour::Traverse;
+
+        let order = vec![3, 0, 4, 1, 6, 8, 7, 9, 5, 2];
+        assert!(tour.apply(&TourOrder::with_ord(order.clone())).is_ok());
+        let collect = |it: &mut dyn Iterator<Item = crate::tour::TourNode>| {
+            it.map(|node| node.index()).collect::<Vec<_>>()
+        };
+
+        let from = tour.get(1).unwrap();
+        let expected = vec![1, 6, 8, 7, 9, 5, 2, 3, 0, 4];
+        assert_eq!(expected, collect(&mut tour.traverse(&from)));
+
+        let backward: Vec<_> = expected[1..].iter().rev().copied().collect();
+        assert_eq!(
+            [vec![1], backward.clone()].concat(),
+            collect(&mut tour.traverse_rev(&from))
+        );
+        assert_eq!(
+            expected.iter().rev().copied().collect::<Vec<_>>(),
+            collect(&mut tour.traverse(&from).rev())
+        );
+        assert_eq!(Some(4), tour.traverse(&from).last().map(|n| n.index()));
+
+        let (a, b) = (tour.get(8).unwrap(), tour.get(3).unwrap());
+        assert_eq!(vec![8, 7, 9, 5, 2, 3], collect(&mut tour.path(&a, &b)));
+        assert_eq!(
+            vec![3, 2, 5, 9, 7, 8],
+            collect(&mut tour.path(&a, &b).rev())
+        );
+        assert_eq!(vec![8, 6, 1, 4, 0, 3], collect(&mut tour.path_rev(&a, &b)));
+        assert_eq!(vec![8], collect(&mut tour.path(&a, &a)));
+
+        // Both ends meet in the middle.
+        let mut it = tour.path(&a, &b);
+        let mut result = Vec::new();
+        while let (Some(x), y) = (it.next(), it.next_back()) {
+            result.push(x.index());
+            result.extend(y.map(|n| n.index()));
+        }
+        assert_eq!(vec![8, 3, 7, 2, 9, 5], result);
+
+        tour.rev();
+        assert_eq!(
+            [vec![1], backward].concat(),
+            collect(&mut tour.traverse(&from))
+        );
+
+        assert_eq!(tour.len(), tour.itr().len());
+        assert_eq!(Some(tour.len() - 1), tour.itr().last().map(|n| n.index()));
+        assert_eq!(
+            (0..tour.len()).rev().collect::<Vec<_>>(),
+            collect(&mut tour.itr().rev())
+        );
+    }
+
+    // Applies random flips, interleaved with reversals of the tour, and compares the result
+    // against a plain vector after each flip.
+    pub fn flip_random(tour: &mut impl Tour) {
+        use rand::{Rng, SeedableRng};
+
+        let n_nodes = tour.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(n_nodes as u64);
+        let mut expected: Vec<usize> = (0..n_nodes).collect();
+        assert!(tour.apply(&TourOrder::with_nat_ord(n_nodes)).is_ok());
+
+        for _ in 0..500 {
+            let (ii, jj) = (rng.gen_range(0..n_nodes), rng.gen_range(0..n_nodes));
+            let (a, b) = (expected[ii], expected[(ii + 1) % n_nodes]);
+            let (c, d) = (expected[jj], expected[(jj + 1) % n_nodes]);
+            if a == c || b == c || a == d {
+                continue;
+            }
+
+            // Reverses the path (b, c) in the expected order.
+            let len = (jj + n_nodes - ii) % n_nodes;
+            for kk in 0..len / 2 {
+                expected.swap((ii + 1 + kk) % n_nodes, (jj + n_nodes - kk) % n_nodes);
+            }
+
+            if rng.gen_bool(0.2) {
+                tour.rev();
+            }
+
+            match tour.successor_at(a) == tour.get(b) {
+                true => tour.flip_at(a, b, c, d),
+                false => tour.flip_at(b, a, d, c),
+            }
+
+            let expected = TourOrder::with_ord(expected.clone());
+            test_tour_order(tour, &expected);
+            crate::tour::tests::approx_eq!(
+                f64,
+                tour.measure(&expected),
+                tour.total_distance(),
+                epsilon = 1e-6
+            );
+        }
+    }
+
+    pub fn move_segment(tour: &mut impl Tour) {
+        use rand::{Rng, SeedableRng};
+
+        let n_nodes = tour.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(n_nodes as u64);
+        let mut expected: Vec<usize> = (0..n_nodes).collect();
+        assert!(tour.apply(&TourOrder::with_nat_ord(n_nodes)).is_ok());
+
+        for step in 0..400 {
+            if rng.gen_bool(0.2) {
+                tour.rev();
+            }
+
+            // Follows the direction of the tour, which may change with every flip.
+            if tour.successor_at(expected[0]) != tour.get(expected[1]) {
+                expected.reverse();
+            }
+
+            // A path of 1 to 3 nodes, or a longer one spanning segments, and a target node
+            // outside of it.
+            let k = if step % 4 == 0 {
+                rng.gen_range(1..n_nodes - 1)
+            } else {
+                rng.gen_range(1..=3)
+            };
+            let ii = rng.gen_range(0..n_nodes);
+            let jj = (ii + k + rng.gen_range(0..n_nodes - k)) % n_nodes;
+            let reverse = rng.gen_bool(0.5);
+
+            let (first, last, to) = (
+                tour.get(expected[ii]).unwrap(),
+                tour.get(expected[(ii + k - 1) % n_nodes]).unwrap(),
+                tour.get(expected[jj]).unwrap(),
+            );
+
+            let before = tour.measure(&TourOrder::with_ord(expected.clone()));
+            let delta = tour.move_segment(&first, &last, &to, reverse);
+
+            expected.rotate_left(ii);
+            let mut path: Vec<usize> = expected.drain(..k).collect();
+            if reverse {
+                path.reverse();
+            }
+            let pos = expected.iter().position(|&x| x == to.index()).unwrap();
+            expected.splice(pos + 1..pos + 1, path);
+
+            let order = TourOrder::with_ord(expected.clone());
+            test_tour_order(tour, &order);
+            assert_eq!(Ok(()), tour.validate());
+            crate::tour::tests::approx_eq!(
+                f64,
+                tour.measure(&order) - before,
+                delta,
+                epsilon = 1e-6
+            );
+        }
+    }
+
+    pub fn validate(tour: &mut impl Tour) {
+        use rand::{seq::SliceRandom, Rng, SeedableRng};
+
+        let n_nodes = tour.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(n_nodes as u64);
+        let mut order: Vec<usize> = (0..n_nodes).collect();
+        order.shuffle(&mut rng);
+        assert!(tour.apply(&TourOrder::with_ord(order)).is_ok());
+        assert_eq!(Ok(()), tour.validate());
+
+        for _ in 0..200 {
+            let (a, c) = (rng.gen_range(0..n_nodes), rng.gen_range(0..n_nodes));
+            let b = tour.successor_at(a).unwrap().index();
+            let d = tour.successor_at(c).unwrap().index();
+            if a == c || b == c || a == d {
+                continue;
+            }
+
+            tour.flip_at(a, b, c, d);
+            assert_eq!(Ok(()), tour.validate());
+            crate::tour::tests::approx_eq!(
+                f64,
+                tour.tour_order().cost(),
+                tour.total_distance(),
+                epsilon = 1e-6
+            );
+        }
+    }
+
+    pub fn journal<T: Tour>(tour: T) {
+        use crate::tour::Journal;
+        use rand::{Rng, SeedableRng};
+
+        let n_nodes = tour.len();
+        let mut rng = rand::rngs::StdRng::seed_from_u64(n_nodes as u64);
+        let mut tour = Journal::new(tour);
+
+        // Flips, segment moves and reversals at random.
+        let mut flip_random = |tour: &mut Journal<T>, n_flips: usize| {
+            for _ in 0..n_flips {
+                let (a, c) = (rng.gen_range(0..n_nodes), rng.gen_range(0..n_nodes));
+                let b = tour.successor_at(a).unwrap().index();
+                let d = tour.successor_at(c).unwrap().index();
+                if rng.gen_bool(0.3) {
+                    let (first, last, to) = (
+                        tour.get(a).unwrap(),
+                        tour.get(b).unwrap(),
+                        tour.get(c).unwrap(),
+                    );
+                    if !tour.between(&first, &to, &last) {
+                        tour.move_segment(&first, &last, &to, rng.gen_bool(0.5));
+                    }
+                } else if a != c && b != c && a != d {
+                    tour.flip_at(a, b, c, d);
+                }
+                if rng.gen_bool(0.1) {
+                    tour.rev();
+                }
+            }
+        };
+
+        // Nothing is recorded before the first checkpoint.
+        flip_random(&mut tour, 20);
+        assert_eq!(0, tour.depth());
+
+        let order_0 = tour.tour_order();
+        let cp_0 = tour.checkpoint();
+        flip_random(&mut tour, 30);
+        let order_1 = tour.tour_order();
+        let cp_1 = tour.checkpoint();
+        flip_random(&mut tour, 30);
+
+        assert!(tour.rollback(cp_1) > 0);
+        assert_eq!(order_1.order(), tour.tour_order().order());
+        crate::tour::tests::approx_eq!(f64, order_1.cost(), tour.total_distance(), epsilon = 1e-6);
+
+        tour.rollback(cp_0);
+        assert_eq!(0, tour.depth());
+        assert_eq!(order_0.order(), tour.tour_order().order());
+        assert_eq!(Ok(()), tour.validate());
+
+        // Committed operations are kept.
+        let cp_2 = tour.checkpoint();
+        flip_random(&mut tour, 30);
+        let order_2 = tour.tour_order();
+        tour.commit();
+        assert!(!tour.is_recording());
+        assert_eq!(0, tour.rollback(cp_2));
+        assert_eq!(order_2.order(), tour.tour_order().order());
+
+        // A checkpoint taken before a commit does not revert operations recorded after it.
+        let cp_3 = tour.checkpoint();
+        flip_random(&mut tour, 30);
+        let order_3 = tour.tour_order();
+        assert!(tour.depth() > 0);
+        assert_eq!(0, tour.rollback(cp_2));
+        assert_eq!(order_3.order(), tour.tour_order().order());
+
+        assert!(tour.rollback(cp_3) > 0);
+        assert_eq!(order_2.order(), tour.into_inner().tour_order().order());
+    }
+
+    pub fn clone<T>(mut tour: T)
+    where
+        T: Tour + Clone + Send + 'static,
+    {
+        use rand::{Rng, SeedableRng};
+
+        fn flip_random<T: Tour>(tour: &mut T, seed: u64) {
+            let n_nodes = tour.len();
+            let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+            for _ in 0..50 {
+                let (a, c) = (rng.gen_range(0..n_nodes), rng.gen_range(0..n_nodes));
+                let b = tour.successor_at(a).unwrap().index();
+                let d = tour.successor_at(c).unwrap().index();
+                if a != c && b != c && a != d {
+                    tour.flip_at(a, b, c, d);
+                }
+            }
+        }
+
+        flip_random(&mut tour, 1);
+
+        let copy = tour.clone();
+        assert_eq!(Ok(()), copy.validate());
+        assert_eq!(tour.tour_order().order(), copy.tour_order().order());
+        assert_eq!(tour.total_distance(), copy.total_distance());
+
+        // The copy can be modified on another thread without touching the original.
+        let expected = tour.tour_order();
+        let handle = std::thread::spawn(move || {
+            let mut copy = copy;
+            flip_random(&mut copy, 2);
+            assert_eq!(Ok(()), copy.validate());
+            copy.tour_order()
+        });
+        let changed = handle.join().unwrap();
+
+        assert_ne!(expected.order(), changed.order());
+        assert_eq!(expected.order(), tour.tour_order().order());
+        assert_eq!(Ok(()), tour.validate());
     }
 
     pub fn flip(tour: &mut impl Tour) {
@@ -1,79 +1,52 @@
-use std::ptr::NonNull;
-
 use crate::{
     data::{DataStore, GetIndex, NodeStore},
-    tour::{
-        node::{reverse_int_seg, reverse_segs},
-        NodeStatus,
-    },
+    tour::node::{reverse_int_seg, reverse_seg, reverse_segs, splice, split},
     Scalar,
 };
 
 use super::{
     between,
-    node::{to_nonnull, Segment},
-    NodeRel, Tour, TourIter, TourNode, TourOrder, UpdateTourError,
+    node::{NodeArena, Segment, NIL},
+    segment::{self, Splice},
+    validate::{debug_check_distance, flip_delta, validate_cycle},
+    InvariantError, NodeRel, Tour, TourIter, TourNode, TourOrder, Traverse, UpdateTourError,
 };
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct TwoLevelList {
     store: NodeStore,
-    pub(crate) segments: Vec<Option<NonNull<Segment>>>,
-    nodes: Vec<TourNode>,
+    pub(super) segments: Vec<Segment>,
+    pub(super) nodes: NodeArena,
     total_dist: Scalar,
     rev: bool,
+    /// Number of flips since the segments were last balanced.
+    n_flips: usize,
 }
 
 impl TwoLevelList {
+    /// Creates a tour whose segments hold up to `groupsize` nodes each.
+    ///
+    /// If `groupsize` is zero, it is chosen by [`default_groupsize`](Self::default_groupsize).
     pub fn new<M>(store: &DataStore<M>, groupsize: usize) -> Self {
-        let node_store = store.store();
         let n_nodes = store.len();
+        let groupsize = match groupsize {
+            0 => Self::default_groupsize(n_nodes),
+            _ => groupsize,
+        };
 
-        let mut n_segments = n_nodes / groupsize;
-        if n_nodes % groupsize != 0 {
-            n_segments += 1;
-        }
-
-        let mut segments = Vec::with_capacity(n_segments);
-        segments.push(to_nonnull(Segment::new(0, groupsize)));
-
-        for ii in 1..n_segments {
-            let s = to_nonnull(Segment::new(ii, groupsize));
-
-            if let Some(el) = segments.last() {
-                if let Some(last) = el {
-                    unsafe {
-                        (*s.unwrap().as_ptr()).prev = *el;
-                        (*last.as_ptr()).next = s;
-                    }
-                }
-            }
-
-            if ii == n_segments - 1 {
-                if let Some(el) = segments.first() {
-                    if let Some(first) = el {
-                        unsafe {
-                            (*s.unwrap().as_ptr()).next = *el;
-                            (*first.as_ptr()).prev = s;
-                        }
-                    }
-                }
-            }
-
-            segments.push(s);
-        }
+        let n_segments = n_nodes.div_ceil(groupsize);
 
-        let nodes = node_store
-            .into_iter()
-            .map(|node| TourNode::new(*node))
+        let segments = (0..n_segments.max(1))
+            .map(|ii| Segment::new(ii, groupsize))
             .collect();
 
         let mut result = Self {
-            store: node_store,
-            nodes,
+            store: store.store(),
+            nodes: NodeArena::new(n_nodes),
             segments,
             total_dist: 0.,
             rev: false,
+            n_flips: 0,
         };
 
         result
@@ -82,66 +55,262 @@ impl TwoLevelList {
 
         result
     }
+
+    /// Returns the group size chosen for a tour with `n_nodes` nodes, which is about the square
+    /// root of `n_nodes`.
+    ///
+    /// With this size, both the number of segments and the number of nodes in each segment grow
+    /// with O(√N), which bounds the time taken by `flip` to O(√N).
+    pub fn default_groupsize(n_nodes: usize) -> usize {
+        ((n_nodes as f64).sqrt().ceil() as usize).max(1)
+    }
+
+    /// Creates a tour whose group size is chosen by
+    /// [`default_groupsize`](Self::default_groupsize).
+    pub fn with_default_groupsize<M>(store: &DataStore<M>) -> Self {
+        Self::new(store, 0)
+    }
+
+    /// Returns the maximum number of nodes in a segment when the tour is balanced.
+    pub fn groupsize(&self) -> usize {
+        match self.segments.first() {
+            Some(seg) => seg.max_len,
+            _ => 0,
+        }
+    }
+
+    /// Redistributes the nodes evenly over the segments while keeping the tour and its direction
+    /// unchanged.
+    ///
+    /// Splitting segments during flips moves nodes into neighbouring segments, which may thus
+    /// grow far beyond the group size. A flip rebalances the tour automatically if one of the
+    /// affected segments holds more than twice the group size, but at most once every N flips so
+    /// that the amortised cost stays constant. The operation takes O(N) time.
+    pub fn rebalance(&mut self) {
+        let order: Vec<usize> = match self.nodes.get(0) {
+            Some(first) => self.traverse(&first).map(|node| node.index()).collect(),
+            None => return,
+        };
+
+        self.rev = false;
+        self.layout(&order);
+    }
+
+    /// Returns the number of nodes in a segment.
+    #[inline]
+    pub(super) fn segment_len(&self, seg: &Segment) -> usize {
+        (self.nodes[seg.last].rank - self.nodes[seg.first].rank + 1) as usize
+    }
+
+    /// Returns the segment to which a node belongs.
+    #[inline]
+    fn segment(&self, node: &TourNode) -> Option<&Segment> {
+        if self.nodes.contains(node) {
+            self.segments.get(self.nodes[node.id].segment as usize)
+        } else {
+            None
+        }
+    }
+
+    /// Returns true if the segment of a node holds more than twice as many nodes as it would in
+    /// a balanced tour.
+    #[inline]
+    fn is_oversized(&self, node: &TourNode) -> bool {
+        match self.segment(node) {
+            Some(seg) => self.segment_len(seg) > 2 * seg.max_len,
+            None => false,
+        }
+    }
+
+    /// Distributes the nodes in the given order over the segments and links them, without
+    /// touching their statuses or the total distance of the tour.
+    fn layout(&mut self, order: &[usize]) {
+        let v_len = self.nodes.len();
+        let s_len = self.segments.len() as u32;
+        self.n_flips = 0;
+
+        for (sidx, seg) in self.segments.iter_mut().enumerate() {
+            let sid = sidx as u32;
+            seg.reset();
+            seg.rank = sidx;
+            seg.next = (sid + 1) % s_len;
+            seg.prev = (s_len + sid - 1) % s_len;
+
+            let beg_seg = sidx * seg.max_len;
+            let end_seg = (beg_seg + seg.max_len).min(v_len);
+
+            for iv in beg_seg..end_seg {
+                let v = order[iv] as u32;
+                let node = &mut self.nodes[v];
+                node.predecessor = order[(v_len + iv - 1) % v_len] as u32;
+                node.successor = order[(iv + 1) % v_len] as u32;
+                node.rank = (iv - beg_seg) as i32;
+                node.segment = sid;
+
+                if seg.last == NIL {
+                    seg.first = v;
+                }
+                seg.last = v;
+            }
+        }
+    }
+
+    /// Rearranges the nodes and segments for a flip without updating the total distance.
+    fn flip_segments(
+        &mut self,
+        from_a: &TourNode,
+        to_a: &TourNode,
+        from_b: &TourNode,
+        to_b: &TourNode,
+    ) {
+        let (mut fan, mut tan, mut fbn, mut tbn) = (from_a.id, to_a.id, from_b.id, to_b.id);
+        if self.rev {
+            std::mem::swap(&mut fan, &mut tan);
+            std::mem::swap(&mut fbn, &mut tbn);
+        }
+
+        let (sfa, sta, sfb, stb) = (
+            self.nodes[fan].segment,
+            self.nodes[tan].segment,
+            self.nodes[fbn].segment,
+            self.nodes[tbn].segment,
+        );
+
+        if sfa == NIL || sta == NIL || sfb == NIL || stb == NIL {
+            panic!("DataNode without segment while flipping.");
+        }
+
+        // Case 1: Either the entire path (to_b, from_a) or (to_a, from_b)
+        // resides in the same segment. In this case, we will flip either the
+        // local path or the entire segment if both nodes are the end nodes
+        // of that segment.
+        if sfa == stb && self.is_inner_path(sfa, tbn, fan) {
+            return self.reverse_inner_path(sfa, tbn, fan);
+        } else if sfb == sta && self.is_inner_path(sfb, tan, fbn) {
+            return self.reverse_inner_path(sfb, tan, fbn);
+        }
+
+        // Case 2: Both paths (to_b, from_a) AND (to_a, from_b) consist of a
+        // sequence of consecutive segments. Since to_a and to_b are direct
+        // successors of from_a and from_b, this means that all nodes are
+        // either at the head or the tail of their corresponding segments.
+        // Thus, we only need to reverse these segments.
+        //
+        // Case 1 and 2 are special arrangements of nodes in the tour. A more
+        // general case is when nodes are positioned somewhere in the middle
+        // of their segments. To tackle this case, we will rearrange affected
+        // nodes by splitting their corresponding segments so that the
+        // requirements for case 1 or 2 are satisfied.
+
+        // Check for case 3.
+        let mut is_split = false;
+        if sfa == sta {
+            // split a
+            is_split = true;
+            split(&mut self.nodes, &mut self.segments, sfa, tan);
+        }
+
+        if sfb == stb {
+            // split b
+            is_split = true;
+            split(&mut self.nodes, &mut self.segments, sfb, tbn);
+        }
+
+        if is_split {
+            return self.flip_segments(from_a, to_a, from_b, to_b);
+        }
+
+        // Logic to handle case 2.
+        let (sfa_r, sta_r, sfb_r, stb_r) = (
+            self.segments[sfa as usize].rank,
+            self.segments[sta as usize].rank,
+            self.segments[sfb as usize].rank,
+            self.segments[stb as usize].rank,
+        );
+
+        let diff1 = if sta_r <= sfb_r {
+            sfb_r - sta_r
+        } else {
+            self.segments.len() - sta_r + sfb_r
+        };
+
+        let diff2 = if stb_r <= sfa_r {
+            sfa_r - stb_r
+        } else {
+            self.segments.len() - stb_r + sfa_r
+        };
+
+        if diff1 <= diff2 {
+            // Reverses the path (to_a, from_b).
+            reverse_segs(&mut self.nodes, &mut self.segments, sta, sfb);
+        } else {
+            // Reverses the path (to_b, from_a).
+            reverse_segs(&mut self.nodes, &mut self.segments, stb, sfa);
+        };
+    }
+
+    /// Returns true iff the forward path from `head` to `tail` lies entirely in the segment `seg`.
+    ///
+    /// Both nodes must belong to `seg`.
+    #[inline]
+    fn is_inner_path(&self, seg: u32, head: u32, tail: u32) -> bool {
+        if self.segments[seg as usize].reverse {
+            self.nodes[head].rank >= self.nodes[tail].rank
+        } else {
+            self.nodes[head].rank <= self.nodes[tail].rank
+        }
+    }
+
+    /// Reverses the forward path from `head` to `tail`, which lies entirely in the segment `seg`.
+    ///
+    /// If the path covers the whole segment, only the segment's reverse flag is toggled.
+    #[inline]
+    fn reverse_inner_path(&mut self, seg: u32, head: u32, tail: u32) {
+        let s = &self.segments[seg as usize];
+        let (lo, hi) = if s.reverse {
+            (tail, head)
+        } else {
+            (head, tail)
+        };
+
+        if s.first == lo && s.last == hi {
+            reverse_seg(&mut self.nodes, &mut self.segments, seg);
+        } else {
+            reverse_int_seg(&mut self.nodes, &mut self.segments, seg, lo, hi);
+        }
+    }
+
+    /// Returns the neighbour of a node in the forward (```forward```) or backward traversal of
+    /// the tour.
+    #[inline]
+    fn adjacent(&self, node: &TourNode, forward: bool) -> Option<TourNode> {
+        let seg = self.segment(node)?;
+        let inner = &self.nodes[node.id];
+        let id = if seg.reverse ^ self.rev ^ forward {
+            inner.successor
+        } else {
+            inner.predecessor
+        };
+
+        match id {
+            NIL => None,
+            id => Some(TourNode { id }),
+        }
+    }
 }
 
 impl Tour for TwoLevelList {
     fn apply(&mut self, tour: &super::TourOrder) -> Result<(), UpdateTourError> {
-        self.rev = false;
         let order = tour.order();
         let v_len = self.nodes.len();
-        let s_len = self.segments.len();
+        tour.validate(v_len)?;
 
-        if order.len() != v_len {
-            return Err(UpdateTourError::TourLenMismatched {
-                expected: v_len,
-                received: order.len(),
-            });
-        }
+        self.rev = false;
+        self.layout(order);
 
         self.total_dist = 0.;
-        for (sidx, els) in self.segments.iter().enumerate() {
-            match els {
-                Some(seg) => unsafe {
-                    (*seg.as_ptr()).reset();
-                    (*seg.as_ptr()).rank = sidx;
-                    (*seg.as_ptr()).next = self.segments[(sidx + 1) % s_len];
-                    (*seg.as_ptr()).prev = self.segments[(s_len + sidx - 1) % s_len];
-
-                    let max_len = seg.as_ref().max_len;
-                    let beg_seg = sidx * max_len;
-                    let end_seg = (beg_seg + max_len).min(v_len);
-
-                    for iv in beg_seg..end_seg {
-                        let el_v = self.nodes.get(order[iv]).unwrap();
-                        let el_next = self.nodes.get(order[(iv + 1) % v_len]).unwrap();
-                        let el_prev = self.nodes.get(order[(v_len + iv - 1) % v_len]).unwrap();
-
-                        match (el_v.inner, el_next.inner, el_prev.inner) {
-                            (Some(vtx), Some(vtx_nxt), Some(vtx_prv)) => {
-                                (*vtx.as_ptr()).predecessor = el_prev.inner;
-                                (*vtx.as_ptr()).successor = el_next.inner;
-                                (*vtx.as_ptr()).rank = (iv - beg_seg) as i32;
-                                (*vtx.as_ptr()).segment = *els;
-                                (*vtx.as_ptr()).status = NodeStatus::Active;
-
-                                (*vtx_nxt.as_ptr()).predecessor = el_v.inner;
-                                (*vtx_prv.as_ptr()).successor = el_v.inner;
-
-                                self.total_dist += self
-                                    .store
-                                    .cost(&(*vtx.as_ptr()).index, &(*vtx_nxt.as_ptr()).index);
-                            }
-                            _ => panic!("Nodes not found"),
-                        }
-
-                        if (*seg.as_ptr()).last.is_none() {
-                            (*seg.as_ptr()).first = el_v.inner;
-                        }
-                        (*seg.as_ptr()).last = el_v.inner;
-                    }
-                },
-                None => panic!("Segment not found"),
-            }
+        for (iv, idx) in order.iter().enumerate() {
+            self.total_dist += self.store.cost(idx, &order[(iv + 1) % v_len]);
         }
 
         Ok(())
@@ -149,48 +318,44 @@ impl Tour for TwoLevelList {
 
     #[inline]
     fn between(&self, from: &TourNode, mid: &TourNode, to: &TourNode) -> bool {
-        match (from.inner, mid.inner, to.inner) {
-            (Some(f), Some(m), Some(t)) => unsafe {
-                match (
-                    (*f.as_ptr()).segment,
-                    (*m.as_ptr()).segment,
-                    (*t.as_ptr()).segment,
-                ) {
-                    (Some(sf), Some(sm), Some(st)) => {
-                        match (sf == sm, sm == st, st == sf) {
-                            (true, true, true) => {
-                                (*sf.as_ptr()).reverse
-                                    ^ between(
-                                        (*f.as_ptr()).rank,
-                                        (*m.as_ptr()).rank,
-                                        (*t.as_ptr()).rank,
-                                    )
-                            }
-                            (true, false, false) => {
-                                (*sf.as_ptr()).reverse ^ ((*f.as_ptr()).rank <= (*m.as_ptr()).rank)
-                            }
-                            (false, true, false) => {
-                                (*sm.as_ptr()).reverse ^ ((*m.as_ptr()).rank <= (*t.as_ptr()).rank)
-                            }
-                            (false, false, true) => {
-                                (*st.as_ptr()).reverse ^ ((*t.as_ptr()).rank <= (*f.as_ptr()).rank)
-                            }
-                            (false, false, false) => between(
-                                (*sf.as_ptr()).rank,
-                                (*sm.as_ptr()).rank,
-                                (*st.as_ptr()).rank,
-                            ),
-                            // (true, true, false)
-                            // (true, false, true)
-                            // (false, true, true)
-                            _ => panic!("The transitivity requirement is violated."),
-                        }
-                    }
-                    _ => false,
-                }
-            },
-            _ => panic!("Empty node"),
+        // Coinciding nodes neither depend on the direction of the tour nor on that of a segment.
+        if from == mid || mid == to {
+            return true;
+        }
+        if from == to {
+            return false;
         }
+
+        let (sf, sm, st) = match (self.segment(from), self.segment(mid), self.segment(to)) {
+            (Some(sf), Some(sm), Some(st)) => (sf, sm, st),
+            _ => panic!("Empty node"),
+        };
+
+        let (f, m, t) = (
+            self.nodes[from.id].rank,
+            self.nodes[mid.id].rank,
+            self.nodes[to.id].rank,
+        );
+        let (sfi, smi, sti) = (
+            self.nodes[from.id].segment,
+            self.nodes[mid.id].segment,
+            self.nodes[to.id].segment,
+        );
+
+        let result = match (sfi == smi, smi == sti, sti == sfi) {
+            (true, true, true) => sf.reverse ^ between(f, m, t),
+            (true, false, false) => sf.reverse ^ (f <= m),
+            (false, true, false) => sm.reverse ^ (m <= t),
+            (false, false, true) => st.reverse ^ (t <= f),
+            (false, false, false) => between(sf.rank, sm.rank, st.rank),
+            // (true, true, false)
+            // (true, false, true)
+            // (false, true, true)
+            _ => panic!("The transitivity requirement is violated."),
+        };
+
+        // Reversing the tour's direction inverts the order of three distinct nodes.
+        self.rev ^ result
     }
 
     #[inline]
@@ -207,12 +372,10 @@ impl Tour for TwoLevelList {
 
     #[inline]
     fn distance(&self, a: &TourNode, b: &TourNode) -> Scalar {
-        match (a.inner, b.inner) {
-            (Some(ai), Some(bi)) => unsafe {
-                self.store
-                    .cost(&(*ai.as_ptr()).index, &(*bi.as_ptr()).index)
-            },
-            _ => 0.,
+        if self.nodes.contains(a) && self.nodes.contains(b) {
+            self.store.cost(&a.index(), &b.index())
+        } else {
+            0.
         }
     }
 
@@ -224,6 +387,11 @@ impl Tour for TwoLevelList {
         self.store.cost(a, b)
     }
 
+    #[inline]
+    fn coords(&self, node: &TourNode) -> &[crate::Scalar] {
+        self.store.coords(&node.index())
+    }
+
     #[inline]
     fn flip_at(&mut self, from_a: usize, to_a: usize, from_b: usize, to_b: usize) {
         if let (Some(fa), Some(ta), Some(fb), Some(tb)) = (
@@ -237,197 +405,128 @@ impl Tour for TwoLevelList {
     }
 
     fn flip(&mut self, from_a: &TourNode, to_a: &TourNode, from_b: &TourNode, to_b: &TourNode) {
-        match (from_a.inner, to_a.inner, from_b.inner, to_b.inner) {
-            (Some(mut fan), Some(mut tan), Some(mut fbn), Some(mut tbn)) => unsafe {
-                if self.rev {
-                    std::mem::swap(&mut fan, &mut tan);
-                    std::mem::swap(&mut fbn, &mut tbn);
-                }
+        if ![from_a, to_a, from_b, to_b]
+            .iter()
+            .all(|node| self.nodes.contains(node))
+        {
+            panic!("Node not found while flipping.");
+        }
 
-                match (
-                    (*fan.as_ptr()).segment,
-                    (*tan.as_ptr()).segment,
-                    (*fbn.as_ptr()).segment,
-                    (*tbn.as_ptr()).segment,
-                ) {
-                    (Some(sfa), Some(sta), Some(sfb), Some(stb)) => {
-                        // Case 1: Either the entire path (to_b, from_a) or (to_a, from_b)
-                        // resides in the same segment. In this case, we will flip either the
-                        // local path or the entire segment if both nodes are the end nodes
-                        // of that segment.
-                        if sfa == stb && (*tbn.as_ptr()).rank <= (*fan.as_ptr()).rank {
-                            if ((*sfa.as_ptr()).first == from_a.inner
-                                && (*sfa.as_ptr()).reverse
-                                && (*sfa.as_ptr()).last == to_b.inner)
-                                || ((*sfa.as_ptr()).first == to_b.inner
-                                    && (*sfa.as_ptr()).last == from_a.inner)
-                            {
-                                return (*sfa.as_ptr()).reverse();
-                            }
-                            return reverse_int_seg(&sfa, &tbn, &fan);
-                        } else if sfb == sta && (*tan.as_ptr()).rank <= (*fbn.as_ptr()).rank {
-                            if ((*sfb.as_ptr()).first == from_b.inner
-                                && (*sfb.as_ptr()).reverse
-                                && (*sfb.as_ptr()).last == to_a.inner)
-                                || ((*sfb.as_ptr()).first == to_a.inner
-                                    && (*sfb.as_ptr()).last == from_b.inner)
-                            {
-                                return (*sfb.as_ptr()).reverse();
-                            }
-                            return reverse_int_seg(&sfb, &tan, &fbn);
-                        }
-
-                        // Case 2: Both paths (to_b, from_a) AND (to_a, from_b) consist of a
-                        // sequence of consecutive segments. Since to_a and to_b are direct
-                        // successors of from_a and from_b, this means that all nodes are
-                        // either at the head or the tail of their corresponding segments.
-                        // Thus, we only need to reverse these segments.
-                        //
-                        // Case 1 and 2 are special arrangements of nodes in the tour. A more
-                        // general case is when nodes are positioned somewhere in the middle
-                        // of their segments. To tackle this case, we will rearrange affected
-                        // nodes by splitting their corresponding segments so that the
-                        // requirements for case 1 or 2 are satisfied.
-
-                        // Check for case 3.
-                        let mut split = false;
-                        if sfa == sta {
-                            // split a
-                            split = true;
-                            (*sfa.as_ptr()).split(&tan);
-                        }
-
-                        if sfb == stb {
-                            // split b
-                            split = true;
-                            (*sfb.as_ptr()).split(&tbn);
-                        }
-
-                        if split {
-                            return self.flip(from_a, to_a, from_b, to_b);
-                        }
-
-                        // Logic to handle case 2.
-                        let (sfa_r, sta_r, sfb_r, stb_r) = (
-                            (*sfa.as_ptr()).rank,
-                            (*sta.as_ptr()).rank,
-                            (*sfb.as_ptr()).rank,
-                            (*stb.as_ptr()).rank,
-                        );
-
-                        let diff1 = if sta_r <= sfb_r {
-                            sfb_r - sta_r
-                        } else {
-                            self.segments.len() - sta_r + sfb_r
-                        };
-
-                        let diff2 = if stb_r <= sfa_r {
-                            sfa_r - stb_r
-                        } else {
-                            self.segments.len() - stb_r + sfa_r
-                        };
-
-                        if diff1 <= diff2 {
-                            // Reverses the path (to_a, from_b).
-                            reverse_segs(&sta, &sfb);
-                        } else {
-                            // Reverses the path (to_b, from_a).
-                            reverse_segs(&stb, &sfa);
-                        };
-                    }
-                    _ => panic!("DataNode without segment while flipping."),
-                }
-            },
-            _ => panic!("Nullpointer"),
+        self.flip_segments(from_a, to_a, from_b, to_b);
+        self.total_dist += flip_delta(self, from_a, to_a, from_b, to_b);
+
+        // Rebalancing takes O(N) time, thus it is done at most once every N flips.
+        self.n_flips += 1;
+        if self.n_flips >= self.nodes.len()
+            && [from_a, to_a, from_b, to_b]
+                .iter()
+                .any(|node| self.is_oversized(node))
+        {
+            self.rebalance();
+        }
+
+        debug_check_distance(self);
+
+        #[cfg(feature = "validate")]
+        super::validate::check_flip(self, from_a, to_a, from_b, to_b);
+    }
+
+    /// Splices a path which lies within a single segment into the segment next to `to`, which
+    /// takes O(√N) time for renumbering the nodes on the shorter side of both splice points. A
+    /// path which spans several segments, or covers one entirely, is moved by up to three flips
+    /// instead.
+    fn move_segment(
+        &mut self,
+        first: &TourNode,
+        last: &TourNode,
+        to: &TourNode,
+        reverse: bool,
+    ) -> Scalar {
+        let delta = match segment::prepare(self, first, last, to, reverse) {
+            Splice::Done(delta) => return delta,
+            Splice::Pending(delta) => delta,
+        };
+
+        // The path and the node after which it is inserted, in the internal direction.
+        let (a, b, x) = if self.rev {
+            let d = self.successor(to).unwrap();
+            (last.id, first.id, d.id)
+        } else {
+            (first.id, last.id, to.id)
+        };
+
+        let s = self.nodes[a].segment;
+        let n_path = (self.nodes[a].rank - self.nodes[b].rank).unsigned_abs() as usize + 1;
+        if s == self.nodes[b].segment
+            && self.is_inner_path(s, a, b)
+            && n_path < self.segment_len(&self.segments[s as usize])
+        {
+            splice(&mut self.nodes, &mut self.segments, a, b, x, reverse);
+            self.total_dist += delta;
+
+            self.n_flips += 1;
+            if self.n_flips >= self.nodes.len() && self.is_oversized(first) {
+                self.rebalance();
+            }
+        } else {
+            // The flips update the total distance themselves.
+            segment::move_by_flips(self, first, last, to, reverse);
         }
+
+        debug_check_distance(self);
+
+        #[cfg(feature = "validate")]
+        super::validate::check_move(self, first, last, to);
+
+        delta
     }
 
     #[inline]
     fn get(&self, index: usize) -> Option<TourNode> {
-        self.nodes.get(index).copied()
+        self.nodes.get(index)
     }
 
     #[inline]
     fn relation(&self, base: &TourNode, targ: &TourNode) -> NodeRel {
-        match base.inner {
-            Some(inner) => unsafe {
-                match (*inner.as_ptr()).segment {
-                    Some(seg) => {
-                        match (
-                            (*inner.as_ptr()).predecessor == targ.inner,
-                            (*inner.as_ptr()).successor == targ.inner,
-                            (*seg.as_ptr()).reverse ^ self.rev,
-                        ) {
-                            (true, false, true) | (false, true, false) => NodeRel::Predecessor,
-                            (true, false, false) | (false, true, true) => NodeRel::Successor,
-                            _ => NodeRel::None,
-                        }
-                    }
-                    None => NodeRel::None,
+        match self.segment(base) {
+            Some(seg) => {
+                let inner = &self.nodes[base.id];
+                match (
+                    inner.predecessor == targ.id,
+                    inner.successor == targ.id,
+                    seg.reverse ^ self.rev,
+                ) {
+                    (true, false, true) | (false, true, false) => NodeRel::Predecessor,
+                    (true, false, false) | (false, true, true) => NodeRel::Successor,
+                    _ => NodeRel::None,
                 }
-            },
+            }
             None => NodeRel::None,
         }
     }
 
     #[inline]
     fn successor(&self, node: &TourNode) -> Option<TourNode> {
-        match node.inner {
-            Some(inner) => unsafe {
-                match (*inner.as_ptr()).segment {
-                    Some(seg) => {
-                        if (*seg.as_ptr()).reverse ^ self.rev {
-                            (*inner.as_ptr()).predecessor.map(|_| TourNode {
-                                inner: (*inner.as_ptr()).predecessor,
-                            })
-                        } else {
-                            (*inner.as_ptr()).successor.map(|_| TourNode {
-                                inner: (*inner.as_ptr()).successor,
-                            })
-                        }
-                    }
-                    None => None,
-                }
-            },
-            None => None,
-        }
+        self.adjacent(node, true)
     }
 
     #[inline]
     fn successor_at(&self, kin_index: usize) -> Option<TourNode> {
         match self.nodes.get(kin_index) {
-            Some(kin) => self.successor(kin),
+            Some(kin) => self.successor(&kin),
             None => None,
         }
     }
 
     #[inline]
     fn predecessor(&self, node: &TourNode) -> Option<TourNode> {
-        match node.inner {
-            Some(inner) => unsafe {
-                match (*inner.as_ptr()).segment {
-                    Some(seg) => {
-                        if (*seg.as_ptr()).reverse ^ self.rev {
-                            (*inner.as_ptr()).successor.map(|_| TourNode {
-                                inner: (*inner.as_ptr()).successor,
-                            })
-                        } else {
-                            (*inner.as_ptr()).predecessor.map(|_| TourNode {
-                                inner: (*inner.as_ptr()).predecessor,
-                            })
-                        }
-                    }
-                    None => None,
-                }
-            },
-            None => None,
-        }
+        self.adjacent(node, false)
     }
 
     #[inline]
     fn predecessor_at(&self, kin_index: usize) -> Option<TourNode> {
         match self.nodes.get(kin_index) {
-            Some(kin) => self.predecessor(kin),
+            Some(kin) => self.predecessor(&kin),
             None => None,
         }
     }
@@ -438,37 +537,23 @@ impl Tour for TwoLevelList {
     }
 
     fn tour_order(&self) -> TourOrder {
-        let mut result = Vec::with_capacity(self.nodes.len());
-        let mut d = 0.;
-
-        match self.nodes.first() {
+        match self.nodes.get(0) {
             Some(first) => {
-                result.push(first.index().get());
-                let mut nopt = self.successor(first);
-
-                loop {
-                    match nopt {
-                        Some(node) => {
-                            d += self.distance(&self.predecessor(&node).unwrap(), &node);
-                            if node.inner == first.inner {
-                                break;
-                            }
-
-                            result.push(node.index().get());
-                            nopt = self.successor(&node);
-                        }
-                        None => return TourOrder::default(),
-                    }
+                let order: Vec<usize> = self.traverse(&first).map(|node| node.index()).collect();
+                if order.len() != self.nodes.len() {
+                    return TourOrder::default();
                 }
 
-                TourOrder::with_cost(result, d)
+                let mut result = TourOrder::with_ord(order);
+                result.set_cost(self.measure(&result));
+                result
             }
             None => TourOrder::default(),
         }
     }
 
     fn measure(&self, to: &TourOrder) -> Scalar {
-        if self.len() == to.len() {
+        if !to.is_empty() && self.len() == to.len() {
             let v = to.order();
             let mut cost = self.distance_at(v.first().unwrap(), v.last().unwrap());
             for pair in v.windows(2) {
@@ -480,12 +565,6 @@ impl Tour for TwoLevelList {
         }
     }
 
-    fn reset(&mut self) {
-        for node in &mut self.nodes {
-            node.set_status(NodeStatus::Active);
-        }
-    }
-
     #[inline]
     fn len(&self) -> usize {
         self.nodes.len()
@@ -501,10 +580,71 @@ impl Tour for TwoLevelList {
         self.total_dist
     }
 
-    fn itr(&self) -> TourIter {
-        TourIter {
-            it: self.nodes.iter(),
+    fn validate(&self) -> Result<(), InvariantError> {
+        let s_len = self.segments.len();
+        let n_nodes = self.nodes.len();
+        let mut visited = vec![false; s_len];
+        let mut count = 0;
+
+        for (sidx, seg) in self.segments.iter().enumerate() {
+            // The segments form a ring with consecutive ranks.
+            let rank = seg.rank;
+            let valid = (seg.next as usize) < s_len
+                && (seg.prev as usize) < s_len
+                && rank < s_len
+                && !visited[rank]
+                && self.segments[seg.next as usize].prev == sidx as u32
+                && self.segments[seg.next as usize].rank == (rank + 1) % s_len;
+            if !valid {
+                return Err(InvariantError::BrokenSegment { segment: sidx });
+            }
+            visited[rank] = true;
+
+            // The nodes of a segment are linked from its first to its last node with
+            // consecutive ranks.
+            if seg.first as usize >= n_nodes || seg.last as usize >= n_nodes {
+                return Err(InvariantError::BrokenSegment { segment: sidx });
+            }
+
+            let mut node = seg.first;
+            loop {
+                count += 1;
+                if count > n_nodes || self.nodes[node].segment != sidx as u32 {
+                    return Err(InvariantError::BrokenSegment { segment: sidx });
+                }
+
+                if node == seg.last {
+                    break;
+                }
+
+                match self.nodes[node].successor {
+                    next if (next as usize) < n_nodes
+                        && self.nodes[next].predecessor == node
+                        && self.nodes[next].rank == self.nodes[node].rank + 1 =>
+                    {
+                        node = next
+                    }
+                    _ => {
+                        return Err(InvariantError::BrokenPosition {
+                            node: node as usize,
+                        })
+                    }
+                }
+            }
+        }
+
+        if count != n_nodes {
+            return Err(InvariantError::NotACycle {
+                expected: n_nodes,
+                received: count,
+            });
         }
+
+        validate_cycle(self)
+    }
+
+    fn itr(&self) -> TourIter {
+        self.nodes.itr()
     }
 }
 
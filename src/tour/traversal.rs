@@ -0,0 +1,119 @@
+use super::{Tour, TourNode};
+
+/// Iterators over the nodes of a tour in tour order.
+///
+/// The trait is implemented for every [`Tour`], including [`TourImpltor`](super::TourImpltor).
+pub trait Traverse: Tour + Sized {
+    /// Returns an iterator over all nodes of the tour in its forward traversal, starting at the
+    /// node `from`.
+    fn traverse(&self, from: &TourNode) -> Traversal<'_, Self> {
+        Traversal::new(self, Some(*from), self.predecessor(from), true)
+    }
+
+    /// Returns an iterator over all nodes of the tour in its backward traversal, starting at the
+    /// node `from`.
+    fn traverse_rev(&self, from: &TourNode) -> Traversal<'_, Self> {
+        Traversal::new(self, Some(*from), self.successor(from), false)
+    }
+
+    /// Returns an iterator over the nodes on the forward path from `from` to `to`, both nodes
+    /// included.
+    fn path(&self, from: &TourNode, to: &TourNode) -> Traversal<'_, Self> {
+        Traversal::new(self, Some(*from), Some(*to), true)
+    }
+
+    /// Returns an iterator over the nodes on the backward path from `from` to `to`, both nodes
+    /// included.
+    fn path_rev(&self, from: &TourNode, to: &TourNode) -> Traversal<'_, Self> {
+        Traversal::new(self, Some(*from), Some(*to), false)
+    }
+}
+
+impl<T> Traverse for T where T: Tour {}
+
+/// An iterator over the nodes on a path of a tour.
+///
+/// The path is walked lazily from both of its ends, thus each step takes the time of a single
+/// `successor` or `predecessor` query.
+pub struct Traversal<'s, T> {
+    tour: &'s T,
+    front: Option<TourNode>,
+    back: Option<TourNode>,
+    forward: bool,
+}
+
+impl<'s, T> Traversal<'s, T>
+where
+    T: Tour,
+{
+    fn new(tour: &'s T, front: Option<TourNode>, back: Option<TourNode>, forward: bool) -> Self {
+        let (front, back) = match (front, back) {
+            (Some(f), Some(b)) if f.index() < tour.len() && b.index() < tour.len() => {
+                (Some(f), Some(b))
+            }
+            _ => (None, None),
+        };
+
+        Self {
+            tour,
+            front,
+            back,
+            forward,
+        }
+    }
+
+    #[inline]
+    fn step(&self, node: &TourNode, forward: bool) -> Option<TourNode> {
+        if forward {
+            self.tour.successor(node)
+        } else {
+            self.tour.predecessor(node)
+        }
+    }
+}
+
+impl<'s, T> Iterator for Traversal<'s, T>
+where
+    T: Tour,
+{
+    type Item = TourNode;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.front?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.front = self.step(&node, self.forward);
+        }
+
+        Some(node)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.front {
+            Some(_) => (1, Some(self.tour.len())),
+            None => (0, Some(0)),
+        }
+    }
+}
+
+impl<'s, T> DoubleEndedIterator for Traversal<'s, T>
+where
+    T: Tour,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let node = self.back?;
+        if self.front == self.back {
+            self.front = None;
+            self.back = None;
+        } else {
+            self.back = self.step(&node, !self.forward);
+        }
+
+        Some(node)
+    }
+}
+
+impl<'s, T> std::iter::FusedIterator for Traversal<'s, T> where T: Tour {}
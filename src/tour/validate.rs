@@ -0,0 +1,172 @@
+use crate::Scalar;
+
+use super::{InvariantError, Tour, TourNode};
+
+/// Checks the invariants which are common to all tours, i.e. whether the tour forms a single
+/// cycle with consistent links, whether `between` agrees with that cycle and whether the stored
+/// total distance matches the lengths of its edges.
+pub(super) fn validate_cycle<T>(tour: &T) -> Result<(), InvariantError>
+where
+    T: Tour,
+{
+    let len = tour.len();
+    let first = match tour.get(0) {
+        Some(node) => node,
+        None => return Ok(()),
+    };
+
+    let mut visited = vec![false; len];
+    let mut dist = 0.;
+    let mut node = first;
+
+    for count in 0..len {
+        let idx = node.index();
+        if visited[idx] {
+            return Err(InvariantError::NotACycle {
+                expected: len,
+                received: count,
+            });
+        }
+        visited[idx] = true;
+
+        let (pred, succ) = match (tour.predecessor(&node), tour.successor(&node)) {
+            (Some(pred), Some(succ)) => (pred, succ),
+            _ => return Err(InvariantError::BrokenLink { node: idx }),
+        };
+
+        if tour.predecessor(&succ) != Some(node) || tour.successor(&pred) != Some(node) {
+            return Err(InvariantError::BrokenLink { node: idx });
+        }
+
+        if len >= 3 && (!tour.between(&pred, &node, &succ) || tour.between(&succ, &node, &pred)) {
+            return Err(InvariantError::BrokenOrder { node: idx });
+        }
+
+        dist += tour.distance(&node, &succ);
+        node = succ;
+    }
+
+    if node != first {
+        return Err(InvariantError::BrokenLink {
+            node: first.index(),
+        });
+    }
+
+    let received = tour.total_distance();
+    if !is_close(dist, received) {
+        return Err(InvariantError::DistanceMismatched {
+            expected: dist,
+            received,
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns ```true``` if an incrementally updated distance matches the recomputed one, up to
+/// rounding errors.
+#[inline]
+fn is_close(expected: Scalar, received: Scalar) -> bool {
+    (expected - received).abs() <= 1e-6 * expected.abs().max(1.)
+}
+
+/// Recomputes the total distance of a tour by walking through all of its edges.
+pub(super) fn cycle_distance<T>(tour: &T) -> Scalar
+where
+    T: Tour,
+{
+    let mut node = match tour.get(0) {
+        Some(node) => node,
+        None => return 0.,
+    };
+
+    let mut dist = 0.;
+    for _ in 0..tour.len() {
+        let succ = match tour.successor(&node) {
+            Some(succ) => succ,
+            None => break,
+        };
+        dist += tour.distance(&node, &succ);
+        node = succ;
+    }
+
+    dist
+}
+
+/// Returns the change in the total distance of a tour caused by replacing the edges
+/// `(from_a, to_a)` and `(from_b, to_b)` by `(from_a, from_b)` and `(to_a, to_b)`.
+#[inline]
+pub(super) fn flip_delta<T>(
+    tour: &T,
+    from_a: &TourNode,
+    to_a: &TourNode,
+    from_b: &TourNode,
+    to_b: &TourNode,
+) -> Scalar
+where
+    T: Tour,
+{
+    tour.distance(from_a, from_b) + tour.distance(to_a, to_b)
+        - tour.distance(from_a, to_a)
+        - tour.distance(from_b, to_b)
+}
+
+/// Compares the incrementally updated total distance of a tour after a flip with a full
+/// recomputation, in debug builds only.
+#[inline]
+pub(super) fn debug_check_distance<T>(tour: &T)
+where
+    T: Tour,
+{
+    if cfg!(debug_assertions) {
+        let expected = cycle_distance(tour);
+        debug_assert!(
+            is_close(expected, tour.total_distance()),
+            "Total distance drifted: expected {}, received {}",
+            expected,
+            tour.total_distance()
+        );
+    }
+}
+
+/// Validates a tour after a flip and panics with the offending operation if any invariant is
+/// violated.
+#[cfg(feature = "validate")]
+pub(super) fn check_flip<T>(
+    tour: &T,
+    from_a: &TourNode,
+    to_a: &TourNode,
+    from_b: &TourNode,
+    to_b: &TourNode,
+) where
+    T: Tour,
+{
+    if let Err(err) = tour.validate() {
+        panic!(
+            "Invalid tour after flip({}, {}, {}, {}): {:?}",
+            from_a.index(),
+            to_a.index(),
+            from_b.index(),
+            to_b.index(),
+            err
+        );
+    }
+}
+
+/// Validates a tour after a segment move and panics with the offending operation if any
+/// invariant is violated.
+#[cfg(feature = "validate")]
+pub(super) fn check_move<T>(tour: &T, first: &TourNode, last: &TourNode, to: &TourNode)
+where
+    T: Tour,
+{
+    if let Err(err) = tour.validate() {
+        panic!(
+            "Invalid tour after move_segment({}, {}, {}): {:?}",
+            first.index(),
+            last.index(),
+            to.index(),
+            err
+        );
+    }
+}
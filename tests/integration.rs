@@ -7,3 +7,13 @@ fn test_a280() {
     assert_eq!(0, model.n_depots());
     assert_eq!(280, model.n_nodes());
 }
+
+#[test]
+fn test_from_csv() {
+    use cykl::{data::Metric, input::CsvConfigBuilder, Model};
+
+    let config = CsvConfigBuilder::new().kind(1).x(2).y(3).demand(4).build();
+    let model = Model::from_csv("./tests/data/depots.csv", Metric::Euc2d, 2, &config).unwrap();
+    assert_eq!(1, model.n_depots());
+    assert_eq!(4, model.n_nodes());
+}